@@ -0,0 +1,16 @@
+//! Generates `brex.h` into `OUT_DIR`. Set `BREX_FFI_UPDATE_HEADER` to also update the committed `include/brex.h`.
+
+fn main() {
+    println!("cargo::rerun-if-changed=src/lib.rs");
+    println!("cargo::rerun-if-changed=cbindgen.toml");
+    println!("cargo::rerun-if-env-changed=BREX_FFI_UPDATE_HEADER");
+
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    let config = cbindgen::Config::from_file(format!("{crate_dir}/cbindgen.toml")).unwrap();
+    let bindings = cbindgen::generate_with_config(&crate_dir, config).expect("generate brex.h");
+    bindings.write_to_file(format!("{out_dir}/brex.h"));
+    if std::env::var_os("BREX_FFI_UPDATE_HEADER").is_some() {
+        bindings.write_to_file(format!("{crate_dir}/include/brex.h"));
+    }
+}
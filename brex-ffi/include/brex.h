@@ -0,0 +1,88 @@
+#ifndef BREX_H
+#define BREX_H
+
+/* Generated by cbindgen from brex-ffi/src/lib.rs - do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Status code returned by brex functions
+ */
+typedef enum BrexStatus {
+  /**
+   * The call succeeded
+   */
+  BREX_STATUS_OK = 0,
+  /**
+   * A required pointer argument was null
+   */
+  BREX_STATUS_NULL_POINTER = 1,
+  /**
+   * The input was not valid UTF-8
+   */
+  BREX_STATUS_INVALID_UTF8 = 2,
+  /**
+   * The input could not be encoded
+   */
+  BREX_STATUS_ENCODE_ERROR = 3,
+  /**
+   * The input could not be parsed as brex
+   */
+  BREX_STATUS_DECODE_ERROR = 4,
+  /**
+   * The output contained an interior nul byte, and can't be returned as a C string
+   */
+  BREX_STATUS_INTERIOR_NUL = 5,
+  /**
+   * brex panicked while handling the input
+   */
+  BREX_STATUS_PANIC = 6,
+} BrexStatus;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Encode the nul-terminated UTF-8 string `input` to brex, writing the result to `*output`.
+ *
+ * The result must be released with [`brex_free()`].
+ *
+ * # Safety
+ * `input` must be a valid nul-terminated string, and `output` must be valid for writes.
+ */
+enum BrexStatus brex_encode(const char *input, char **output);
+
+/**
+ * Parse & expand the nul-terminated brex string `input`, writing the plaintext result to `*output`.
+ *
+ * The result must be released with [`brex_free()`].
+ *
+ * # Safety
+ * `input` must be a valid nul-terminated string, and `output` must be valid for writes.
+ */
+enum BrexStatus brex_decode(const char *input, char **output);
+
+/**
+ * Release a string returned by [`brex_encode()`] or [`brex_decode()`]. Null is ignored.
+ *
+ * # Safety
+ * `s` must be null, or a string returned by this library that has not already been freed.
+ */
+void brex_free(char *s);
+
+/**
+ * The message of the last error that occurred on this thread, or null if there has been none.
+ *
+ * The string is owned by the library, and is valid until the next failing call on the same thread.
+ */
+const char *brex_last_error(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* BREX_H */
@@ -0,0 +1,127 @@
+//! C ABI for encoding & decoding brex strings.
+//!
+//! Strings returned by [`brex_encode()`] & [`brex_decode()`] are owned by the caller, and must be released with [`brex_free()`].
+//! On failure, a description of the error can be retrieved with [`brex_last_error()`].
+//!
+//! The C header is committed at `include/brex.h`; regenerate it with `BREX_FFI_UPDATE_HEADER=1 cargo build -p brex-ffi`.
+#![deny(missing_docs)]
+#![deny(warnings)]
+
+use std::{
+    cell::RefCell,
+    ffi::{CStr, CString, c_char},
+    panic::{self, AssertUnwindSafe},
+    ptr,
+};
+
+/// Status code returned by brex functions
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BrexStatus {
+    /// The call succeeded
+    Ok = 0,
+    /// A required pointer argument was null
+    NullPointer = 1,
+    /// The input was not valid UTF-8
+    InvalidUtf8 = 2,
+    /// The input could not be encoded
+    EncodeError = 3,
+    /// The input could not be parsed as brex
+    DecodeError = 4,
+    /// The output contained an interior nul byte, and can't be returned as a C string
+    InteriorNul = 5,
+    /// brex panicked while handling the input
+    Panic = 6,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(status: BrexStatus, err: impl std::fmt::Display) -> BrexStatus {
+    // interior nuls can only come from user input echoed into the error, so strip them rather than lose the message
+    let message = err.to_string().replace('\0', "");
+    LAST_ERROR.with(|last| *last.borrow_mut() = CString::new(message).ok());
+    status
+}
+
+/// Run `f` over the C string `input`, writing its result to `output`
+///
+/// # Safety
+/// `input` must be null or a valid nul-terminated string, `output` must be null or valid for writes.
+unsafe fn with_str<E: std::fmt::Display>(
+    input: *const c_char,
+    output: *mut *mut c_char,
+    on_error: BrexStatus,
+    f: impl FnOnce(&str) -> Result<String, E>,
+) -> BrexStatus {
+    if input.is_null() || output.is_null() {
+        return set_last_error(BrexStatus::NullPointer, "input and output must not be null");
+    }
+    // SAFETY: checked for null above, validity is up to the caller
+    let input = match unsafe { CStr::from_ptr(input) }.to_str() {
+        Ok(input) => input,
+        Err(err) => return set_last_error(BrexStatus::InvalidUtf8, err),
+    };
+    // unwinding into C is undefined behaviour
+    let result = match panic::catch_unwind(AssertUnwindSafe(|| f(input))) {
+        Ok(Ok(result)) => result,
+        Ok(Err(err)) => return set_last_error(on_error, err),
+        Err(_) => return set_last_error(BrexStatus::Panic, "brex panicked"),
+    };
+    match CString::new(result) {
+        Ok(result) => {
+            // SAFETY: checked for null above, validity is up to the caller
+            unsafe { *output = result.into_raw() };
+            BrexStatus::Ok
+        }
+        Err(err) => set_last_error(BrexStatus::InteriorNul, err),
+    }
+}
+
+/// Encode the nul-terminated UTF-8 string `input` to brex, writing the result to `*output`.
+///
+/// The result must be released with [`brex_free()`].
+///
+/// # Safety
+/// `input` must be a valid nul-terminated string, and `output` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn brex_encode(input: *const c_char, output: *mut *mut c_char) -> BrexStatus {
+    unsafe { with_str(input, output, BrexStatus::EncodeError, brex::encode) }
+}
+
+/// Parse & expand the nul-terminated brex string `input`, writing the plaintext result to `*output`.
+///
+/// The result must be released with [`brex_free()`].
+///
+/// # Safety
+/// `input` must be a valid nul-terminated string, and `output` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn brex_decode(input: *const c_char, output: *mut *mut c_char) -> BrexStatus {
+    unsafe { with_str(input, output, BrexStatus::DecodeError, brex::decode) }
+}
+
+/// Release a string returned by [`brex_encode()`] or [`brex_decode()`]. Null is ignored.
+///
+/// # Safety
+/// `s` must be null, or a string returned by this library that has not already been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn brex_free(s: *mut c_char) {
+    if !s.is_null() {
+        // SAFETY: the string was created by `CString::into_raw`, as guaranteed by the caller
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
+
+/// The message of the last error that occurred on this thread, or null if there has been none.
+///
+/// The string is owned by the library, and is valid until the next failing call on the same thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn brex_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map(|err| err.as_ptr())
+            .unwrap_or(ptr::null())
+    })
+}
@@ -0,0 +1,58 @@
+//! Builds `tests/roundtrip.c` against the cdylib, and runs it over the brex case files.
+#![cfg(unix)]
+
+use std::{path::PathBuf, process::Command};
+
+#[test]
+fn c_roundtrip() {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    // integration tests live in `<target>/<profile>/deps`. `cargo test` only builds the cdylib there,
+    // while `cargo build` also copies it up to `<target>/<profile>`, so search both.
+    let deps_dir = std::env::current_exe()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .to_path_buf();
+    let lib_dirs = [deps_dir.parent().unwrap().to_path_buf(), deps_dir];
+    let exe = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("brex_roundtrip");
+
+    let status = Command::new(std::env::var("CC").unwrap_or_else(|_| "cc".into()))
+        .arg(manifest_dir.join("tests/roundtrip.c"))
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .args(lib_dirs.iter().flat_map(|dir| {
+            [
+                "-L".into(),
+                dir.clone().into_os_string(),
+                format!("-Wl,-rpath,{}", dir.display()).into(),
+            ]
+        }))
+        .arg("-lbrex_ffi")
+        .arg("-o")
+        .arg(&exe)
+        .status()
+        .expect("run C compiler");
+    assert!(status.success(), "compiling roundtrip.c failed");
+
+    let cases = manifest_dir.join("../brex/cases");
+    let mut cases = std::fs::read_dir(cases)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<_>>();
+    cases.sort();
+    assert!(!cases.is_empty());
+
+    let status = Command::new(&exe).args(&cases).status().unwrap();
+    assert!(status.success(), "C roundtrip failed");
+}
+
+#[test]
+fn header_is_current() {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let committed = std::fs::read_to_string(manifest_dir.join("include/brex.h")).unwrap();
+    let generated = std::fs::read_to_string(concat!(env!("OUT_DIR"), "/brex.h")).unwrap();
+    assert!(
+        committed == generated,
+        "include/brex.h is stale, regenerate it with `BREX_FFI_UPDATE_HEADER=1 cargo build -p brex-ffi`"
+    );
+}
@@ -0,0 +1,71 @@
+/*
+ * Round-trips every line of the given brex case files through brex_encode & brex_decode.
+ * Lines starting with '#' are comments.
+ *
+ * usage: roundtrip <case file>...
+ */
+#include <stdio.h>
+#include <string.h>
+
+#include "brex.h"
+
+static int roundtrip_line(const char *line) {
+    char *encoded = NULL;
+    char *decoded = NULL;
+    int failed = 1;
+
+    if (brex_encode(line, &encoded) != BREX_STATUS_OK) {
+        fprintf(stderr, "encode %s: %s\n", line, brex_last_error());
+        goto out;
+    }
+    if (brex_decode(encoded, &decoded) != BREX_STATUS_OK) {
+        fprintf(stderr, "decode %s: %s\n", encoded, brex_last_error());
+        goto out;
+    }
+    if (strcmp(line, decoded) != 0) {
+        fprintf(stderr, "mismatch:\n  input:   %s\n  encoded: %s\n  decoded: %s\n", line, encoded, decoded);
+        goto out;
+    }
+    failed = 0;
+
+out:
+    brex_free(encoded);
+    brex_free(decoded);
+    return failed;
+}
+
+static int roundtrip_file(const char *path) {
+    static char line[1 << 16];
+    int failures = 0;
+    FILE *file = fopen(path, "r");
+    if (!file) {
+        perror(path);
+        return 1;
+    }
+    while (fgets(line, sizeof line, file)) {
+        line[strcspn(line, "\r\n")] = '\0';
+        if (line[0] == '#' || line[0] == '\0') {
+            continue;
+        }
+        failures += roundtrip_line(line);
+    }
+    fclose(file);
+    return failures;
+}
+
+int main(int argc, char **argv) {
+    int failures = 0;
+    char *out = NULL;
+
+    if (brex_encode(NULL, &out) != BREX_STATUS_NULL_POINTER || brex_last_error() == NULL) {
+        fprintf(stderr, "null input was not reported\n");
+        failures++;
+    }
+    for (int i = 1; i < argc; i++) {
+        failures += roundtrip_file(argv[i]);
+    }
+    if (failures) {
+        fprintf(stderr, "%d failure(s)\n", failures);
+    }
+    return failures != 0;
+}
@@ -0,0 +1,125 @@
+//! Compile-time validation & expansion of brex string literals.
+//!
+//! [`brex!`] parses its literal when the crate is compiled, so a malformed pattern is a compiler error
+//! rather than a name that silently decodes to plaintext at runtime.
+#![deny(missing_docs)]
+#![deny(warnings)]
+
+use brex::{Brex, parse::Error};
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{
+    LitStr, Token,
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+};
+
+mod kw {
+    syn::custom_keyword!(components);
+}
+
+/// What a literal expands to
+enum Output {
+    /// The decoded `&'static str`
+    Decoded,
+    /// A `&'static [&'static str]` of [`Brex::components()`]
+    Components,
+}
+
+struct Input {
+    output: Output,
+    literal: LitStr,
+}
+
+impl Parse for Input {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let output = match input.peek(kw::components) {
+            true => {
+                input.parse::<kw::components>()?;
+                Output::Components
+            }
+            false => Output::Decoded,
+        };
+        let literal = input.parse()?;
+        input.parse::<Option<Token![,]>>()?;
+        Ok(Self { output, literal })
+    }
+}
+
+/// Validate & expand a brex literal at compile time.
+///
+/// `brex!("...")` expands to the decoded `&'static str`,
+/// and `brex!(components "...")` to a `&'static [&'static str]` of its [`Brex::components()`].
+///
+/// ```
+/// use brex_macros::brex;
+///
+/// const SKINS: &str = brex!("ahri❮_skin{1,2,3}❯.bin");
+/// assert_eq!(SKINS, "ahri_skin1_skin2_skin3.bin");
+///
+/// const PARTS: &[&str] = brex!(components "ahri❮_skin{1,2,3}❯.bin");
+/// assert_eq!(PARTS, ["ahri", "_skin1", "_skin2", "_skin3", ".bin"]);
+/// ```
+///
+/// A malformed brex block is an error naming the character it stopped parsing at.
+/// The error only points at that character on nightly compilers; on stable, it spans the whole literal:
+///
+/// ```compile_fail
+/// const SKINS: &str = brex_macros::brex!("ahri❮_skin{1,}❯.bin");
+/// ```
+#[proc_macro]
+pub fn brex(input: TokenStream) -> TokenStream {
+    let Input { output, literal } = parse_macro_input!(input as Input);
+    let value = literal.value();
+    let brex = match Brex::parse_strict(&value) {
+        Ok(brex) => brex,
+        Err(error) => return compile_error(&literal, &value, &error),
+    };
+    match output {
+        Output::Decoded => {
+            let decoded = LitStr::new(&brex.expand(), literal.span());
+            quote!(#decoded)
+        }
+        Output::Components => {
+            let components = brex.components();
+            quote!({
+                const COMPONENTS: &[&str] = &[#(#components),*];
+                COMPONENTS
+            })
+        }
+    }
+    .into()
+}
+
+/// A compiler error for a literal that failed to parse
+fn compile_error(literal: &LitStr, value: &str, error: &Error) -> TokenStream {
+    let position = match error {
+        Error::NumberOverflow { position }
+        | Error::LettersOverflow { position }
+        | Error::Malformed { position } => *position,
+    };
+    let message = match value[position..].chars().next() {
+        Some(ch) => format!("invalid brex literal: {error} ({ch:?})"),
+        None => format!("invalid brex literal: {error} (end of input)"),
+    };
+    syn::Error::new(char_span(literal, value, position), message)
+        .to_compile_error()
+        .into()
+}
+
+/// The span of the character at byte `position` of the literal's value.
+///
+/// Falls back to the whole literal if it contains escapes, or the compiler can't make subspans,
+/// which `proc_macro2` only supports on nightly.
+fn char_span(literal: &LitStr, value: &str, position: usize) -> Span {
+    let token = literal.token();
+    if token.to_string() != format!("\"{value}\"") {
+        return literal.span();
+    }
+    let len = value[position..].chars().next().map_or(0, char::len_utf8);
+    // skip the opening quote
+    token
+        .subspan(1 + position..1 + position + len)
+        .unwrap_or_else(|| literal.span())
+}
@@ -0,0 +1,10 @@
+//! Checks the messages & spans of `brex!` errors.
+//!
+//! The expected `.stderr` files are for a stable compiler, where errors span the whole literal.
+//! Regenerate them with `TRYBUILD=overwrite cargo test -p brex-macros --test ui`.
+
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
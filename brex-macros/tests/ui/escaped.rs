@@ -0,0 +1,5 @@
+const SKINS: &str = brex_macros::brex!("ahri\u{276E}_skin{1,}❯.bin");
+
+fn main() {
+    println!("{SKINS}");
+}
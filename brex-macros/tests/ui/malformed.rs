@@ -0,0 +1,5 @@
+const SKINS: &str = brex_macros::brex!("ahri❮_skin{1,}❯.bin");
+
+fn main() {
+    println!("{SKINS}");
+}
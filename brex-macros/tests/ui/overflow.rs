@@ -0,0 +1,5 @@
+const PARTS: &[&str] = brex_macros::brex!(components "ahri❮_skins{_skin{1,99999999999}}❯.bin");
+
+fn main() {
+    println!("{PARTS:?}");
+}
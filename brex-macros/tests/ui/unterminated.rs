@@ -0,0 +1,5 @@
+const SKINS: &str = brex_macros::brex!("ahri❮_skin{1,2");
+
+fn main() {
+    println!("{SKINS}");
+}
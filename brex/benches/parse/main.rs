@@ -0,0 +1,77 @@
+//! Parsing & decoding benchmarks over `brex/cases/large_dump.in`, comparing against the previous nom parser.
+
+use std::hint::black_box;
+
+use brex::{Brex, Group, Numeric, Suffix, alphabet, parse::Parser};
+use criterion::{Criterion, criterion_group, criterion_main};
+
+mod nom_parser;
+
+fn encoded() -> Vec<String> {
+    include_str!("../../cases/large_dump.in")
+        .lines()
+        .map(|line| brex::encode(line).unwrap())
+        .collect()
+}
+
+fn parse(c: &mut Criterion) {
+    let lines = encoded();
+    let mut group = c.benchmark_group("parse");
+    group.bench_function("nom", |b| {
+        b.iter(|| {
+            for line in &lines {
+                black_box(nom_parser::parse(line).unwrap());
+            }
+        })
+    });
+    group.bench_function("hand_written", |b| {
+        b.iter(|| {
+            for line in &lines {
+                black_box(Brex::parse(line).unwrap());
+            }
+        })
+    });
+    group.bench_function("hand_written_reused", |b| {
+        let mut parser = Parser::new();
+        b.iter(|| {
+            for line in &lines {
+                black_box(parser.parse(line).unwrap());
+            }
+        })
+    });
+    group.finish();
+}
+
+fn decode(c: &mut Criterion) {
+    let lines = encoded();
+    let mut group = c.benchmark_group("decode");
+    group.bench_function("nom", |b| {
+        b.iter(|| {
+            for line in &lines {
+                black_box(nom_parser::parse(line).unwrap().expand());
+            }
+        })
+    });
+    group.bench_function("hand_written", |b| {
+        b.iter(|| {
+            for line in &lines {
+                black_box(brex::decode(line).unwrap());
+            }
+        })
+    });
+    group.bench_function("hand_written_reused", |b| {
+        let mut parser = Parser::new();
+        let mut out = String::new();
+        b.iter(|| {
+            for line in &lines {
+                out.clear();
+                parser.decode_into(line, &mut out).unwrap();
+                black_box(&out);
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, parse, decode);
+criterion_main!(benches);
@@ -0,0 +1,165 @@
+//! [`Arbitrary`] impls for the brex IR, and generators for realistic League of Legends file names.
+//!
+//! Enabled by the `arbitrary` feature.
+
+use alloc::{string::String, vec::Vec};
+
+use ::arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::{
+    Alphabetic, Brex, Group, Numeric, Suffix,
+    builder::{POSTAMBLE_RESERVED, PREAMBLE_RESERVED, PREFIX_RESERVED, SUFFIX_RESERVED},
+    collation,
+};
+
+/// Take arbitrary text, cut short at the first reserved character
+fn text<'a>(u: &mut Unstructured<'a>, reserved: &[char]) -> Result<&'a str> {
+    let text = <&'a str>::arbitrary(u)?;
+    Ok(&text[..text.find(reserved).unwrap_or(text.len())])
+}
+
+impl<'a> Arbitrary<'a> for Numeric {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let start = u32::arbitrary(u)?;
+        Ok(match bool::arbitrary(u)? {
+            true => Numeric::Single(start),
+            false => Numeric::Range(start, u.int_in_range(start..=u32::MAX)?),
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Alphabetic {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let start = u32::arbitrary(u)?;
+        Ok(match bool::arbitrary(u)? {
+            true => Alphabetic::Single(start),
+            false => Alphabetic::Range(start, u.int_in_range(start..=u32::MAX)?),
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Suffix<'a> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let suffix = match text(u, SUFFIX_RESERVED)?.trim() {
+            "" => "_",
+            suffix => suffix,
+        };
+        Ok(match u.int_in_range(0..=2u8)? {
+            0 => Suffix::simple(suffix),
+            1 => {
+                let mut numerics = Vec::<Numeric>::arbitrary(u)?;
+                if numerics.is_empty() {
+                    numerics.push(Numeric::arbitrary(u)?);
+                }
+                Suffix::numeric(suffix, numerics)
+            }
+            _ => {
+                let mut letters = Vec::<Alphabetic>::arbitrary(u)?;
+                if letters.is_empty() {
+                    letters.push(Alphabetic::arbitrary(u)?);
+                }
+                Suffix::alphabetic(suffix, letters)
+            }
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Group<'a> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let prefix = text(u, PREFIX_RESERVED)?.trim();
+        let mut suffixes = Vec::<Suffix>::arbitrary(u)?;
+        if suffixes.is_empty() {
+            suffixes.push(Suffix::arbitrary(u)?);
+        }
+        Ok(Group { prefix, suffixes })
+    }
+}
+
+/// Generates brex that can be displayed (compact or pretty) & parsed back to an identical [`Brex`]
+impl<'a> Arbitrary<'a> for Brex<'a> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let preamble = Some(text(u, PREAMBLE_RESERVED)?).filter(|s| !s.is_empty());
+        let groups = Vec::<Group>::arbitrary(u)?;
+        let postamble = match groups.is_empty() {
+            true => None,
+            false => Some(text(u, POSTAMBLE_RESERVED)?).filter(|s| !s.is_empty()),
+        };
+        Ok(Brex {
+            preamble,
+            groups,
+            postamble,
+        })
+    }
+}
+
+/// Champion & other bin owner names
+const OWNERS: &[&str] = &[
+    "aatrox",
+    "ahri",
+    "kaisa",
+    "missfortune",
+    "pettftavatar",
+    "tft_itemtrait",
+];
+/// Linked bin folders
+const FOLDERS: &[&str] = &[
+    "skins",
+    "base",
+    "bubbletea",
+    "evil",
+    "set5launchbattlepass",
+    "superfan",
+    "superfanvariant",
+    "superhero",
+];
+/// Linked bin file stems, followed by a number
+const NUMBERED_FILES: &[&str] = &["skin", "tier"];
+
+/// A realistic concatenated League of Legends bin name, such as `aatrox_skins_root_skins_skin0_skins_skin1.bin`.
+///
+/// Folders & files are ordered the same way the game orders them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeagueName(pub String);
+
+impl<'a> Arbitrary<'a> for LeagueName {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut name = String::from(*u.choose(OWNERS)?);
+
+        let folder_count = u.int_in_range(1..=FOLDERS.len())?;
+        let mut folders = Vec::with_capacity(folder_count);
+        for _ in 0..folder_count {
+            folders.push(*u.choose(FOLDERS)?);
+        }
+        collation::sort(&mut folders);
+        folders.dedup();
+
+        for folder in folders {
+            let mut files = Vec::new();
+            if bool::arbitrary(u)? {
+                files.push(String::from("root"));
+            }
+            let stem = *u.choose(NUMBERED_FILES)?;
+            // skin numbers are mostly contiguous, with a few gaps
+            let mut number = u.int_in_range(0..=5)?;
+            for _ in 0..u.int_in_range(0..=40)? {
+                files.push(alloc::format!("{stem}{number}"));
+                number += u.int_in_range(1..=3)?;
+            }
+            if files.is_empty() {
+                files.push(String::from("root"));
+            }
+            // the game sorts the numbered files as strings, so `skin10` comes before `skin2`
+            let numbered = usize::from(files[0] == "root");
+            files[numbered..].sort();
+
+            for file in files {
+                name.push('_');
+                name.push_str(folder);
+                name.push('_');
+                name.push_str(&file);
+            }
+        }
+        name.push_str(".bin");
+        Ok(Self(name))
+    }
+}
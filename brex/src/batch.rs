@@ -0,0 +1,156 @@
+//! Encoding many lines in parallel
+//!
+//! Lines are read in chunks of [`BatchOptions::chunk_size`], each chunk is encoded in parallel with rayon,
+//! and results are yielded in input order, so arbitrarily large inputs can be streamed.
+//!
+//! See [`encode_lines()`] and [`encode_reader()`]
+
+use std::{collections::VecDeque, io::BufRead};
+
+use rayon::iter::{IntoParallelIterator as _, ParallelIterator as _};
+
+use crate::{
+    Brex,
+    encode::{self, EncodeOptions, Fallback},
+};
+
+/// Error encoding a batch of lines
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Error reading input - {0}")]
+    /// Error reading a line of input
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    /// Error encoding a line
+    Encode(#[from] encode::Error),
+}
+
+/// Options for [`encode_lines()`] and [`encode_reader()`]
+#[derive(Debug, Clone)]
+pub struct BatchOptions {
+    /// Options used to encode each line
+    pub encode: EncodeOptions,
+    /// Whether to verify each line with [`Brex::encode_checked_with()`]
+    pub checked: bool,
+    /// How many lines to read ahead & encode in parallel at once
+    pub chunk_size: usize,
+    /// Whether to pass blank lines & lines starting with `#` through unencoded, as [`crate::document`] comments
+    pub comments: bool,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        Self {
+            encode: EncodeOptions::default(),
+            checked: true,
+            chunk_size: 4096,
+            comments: false,
+        }
+    }
+}
+
+/// A single encoded line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Encoded {
+    /// The input line, with surrounding whitespace trimmed
+    pub input: String,
+    /// The encoded brex string
+    pub encoded: String,
+    /// The fallback taken, if the line was checked and the regular encoding did not roundtrip
+    pub fallback: Option<Fallback>,
+    /// Whether the line is a comment or blank, so was passed through unencoded. See [`BatchOptions::comments`].
+    pub comment: bool,
+}
+
+/// Iterator over encoded lines, in input order. Created by [`encode_lines()`] and [`encode_reader()`].
+pub struct EncodeLines<I> {
+    lines: I,
+    options: BatchOptions,
+    pending: VecDeque<Result<Encoded, Error>>,
+}
+
+/// Encode each line in parallel, yielding results in input order.
+pub fn encode_lines<I>(
+    lines: I,
+    options: &BatchOptions,
+) -> EncodeLines<impl Iterator<Item = Result<String, Error>>>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    EncodeLines::new(
+        lines.into_iter().map(|line| Ok(line.as_ref().to_string())),
+        options,
+    )
+}
+
+/// Encode each line read from `reader` in parallel, yielding results in input order.
+pub fn encode_reader<R: BufRead>(
+    reader: R,
+    options: &BatchOptions,
+) -> EncodeLines<impl Iterator<Item = Result<String, Error>>> {
+    EncodeLines::new(
+        reader.lines().map(|line| line.map_err(Error::from)),
+        options,
+    )
+}
+
+impl<I> EncodeLines<I> {
+    fn new(lines: I, options: &BatchOptions) -> Self {
+        Self {
+            lines,
+            options: options.clone(),
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+impl<I: Iterator<Item = Result<String, Error>>> Iterator for EncodeLines<I> {
+    type Item = Result<Encoded, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pending.is_empty() {
+            let chunk = self
+                .lines
+                .by_ref()
+                .take(self.options.chunk_size.max(1))
+                .collect::<Vec<_>>();
+            let options = &self.options;
+            self.pending = chunk
+                .into_par_iter()
+                .map(|line| encode_line(line?, options))
+                .collect::<Vec<_>>()
+                .into();
+        }
+        self.pending.pop_front()
+    }
+}
+
+fn encode_line(line: String, options: &BatchOptions) -> Result<Encoded, Error> {
+    if options.comments && (line.starts_with('#') || line.trim().is_empty()) {
+        return Ok(Encoded {
+            input: line,
+            encoded: String::new(),
+            fallback: None,
+            comment: true,
+        });
+    }
+    let input = line.trim();
+    let folded = options.encode.case.fold(input);
+    let (encoded, fallback) = match options.checked {
+        true => {
+            let checked = Brex::encode_checked_with(&folded, &options.encode)?;
+            (checked.brex.to_string(), checked.fallback)
+        }
+        false => (
+            Brex::encode_with(&folded, &options.encode)?.to_string(),
+            None,
+        ),
+    };
+    Ok(Encoded {
+        input: input.to_string(),
+        encoded,
+        fallback,
+        comment: false,
+    })
+}
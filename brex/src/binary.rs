@@ -0,0 +1,290 @@
+//! Compact binary serialisation of the brex IR
+//!
+//! The text form spends multi-byte characters on its delimiters & writes numbers in decimal.
+//! The binary form instead uses varints, and stores each distinct string once in a string table.
+//!
+//! Layout, version 1. All integers are unsigned LEB128 varints, unless noted.
+//! ```text
+//! magic       "brex" (4 bytes)
+//! version     1 (1 byte)
+//! strings     count, then each string as its byte length & UTF-8 bytes
+//! preamble    0 for none, otherwise 1 + its string index
+//! postamble   0 for none, otherwise 1 + its string index
+//! groups      count, then each group as:
+//!   prefix    string index
+//!   suffixes  count, then each suffix as:
+//!     suffix  string index
+//!     blocks  (1 byte) bit 0 set if it has numerics, bit 1 set if it has letters
+//!     values  for numerics then letters, if present: count, then each value as:
+//!       start << 1 | tag    tag is 0 for `Single` & 1 for `Range`
+//!       end - start         only for `Range`, zigzag encoded as ranges may be reversed
+//! ```
+//! Strings are borrowed from the input when deserialising, so [`Brex::from_bytes()`] does not copy them.
+//!
+//! See [`Brex::to_bytes()`] and [`Brex::from_bytes()`]
+
+use alloc::{collections::BTreeMap, vec::Vec};
+
+use crate::{Alphabetic, Brex, Group, Numeric, Suffix};
+
+/// Starts every serialised brex
+pub const MAGIC: &[u8; 4] = b"brex";
+/// The latest layout version
+pub const VERSION: u8 = 1;
+
+/// Error deserialising a brex
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    #[error("input does not start with {MAGIC:?}")]
+    /// The input is not a serialised brex
+    InvalidMagic,
+    #[error("unsupported layout version {0}, expected at most {VERSION}")]
+    /// The input is from a newer version
+    UnsupportedVersion(u8),
+    #[error("unexpected end of input")]
+    /// The input ended part way through
+    UnexpectedEof,
+    #[error("varint at byte {0} does not fit in its value")]
+    /// A varint is too large
+    Overflow(usize),
+    #[error("string {0} is not valid UTF-8")]
+    /// A string in the string table is not valid UTF-8
+    InvalidString(usize),
+    #[error("string index {0} is out of bounds")]
+    /// A string index does not refer to the string table
+    InvalidIndex(u64),
+    #[error("range end at byte {0} does not fit in a u32")]
+    /// A range's end does not fit in a [`u32`]
+    InvalidRange(usize),
+    #[error("{0} trailing bytes after the brex")]
+    /// There is data after the brex
+    TrailingBytes(usize),
+}
+
+const NUMERICS: u8 = 1 << 0;
+const LETTERS: u8 = 1 << 1;
+
+impl<'a> Brex<'a> {
+    /// Serialise to the compact binary layout. See the [module docs](crate::binary).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut strings = Strings::default();
+        let preamble = self.preamble.map(|s| strings.index(s));
+        let postamble = self.postamble.map(|s| strings.index(s));
+        let mut body = Vec::new();
+        write_varint(&mut body, preamble.map_or(0, |i| i + 1));
+        write_varint(&mut body, postamble.map_or(0, |i| i + 1));
+        write_varint(&mut body, self.groups.len() as u64);
+        for group in &self.groups {
+            write_varint(&mut body, strings.index(group.prefix));
+            write_varint(&mut body, group.suffixes.len() as u64);
+            for suffix in &group.suffixes {
+                write_varint(&mut body, strings.index(suffix.suffix));
+                let numerics = suffix.numerics.as_deref();
+                let letters = suffix.letters.as_deref();
+                body.push(numerics.map_or(0, |_| NUMERICS) | letters.map_or(0, |_| LETTERS));
+                if let Some(numerics) = numerics {
+                    write_values(
+                        &mut body,
+                        numerics
+                            .iter()
+                            .map(|n| (n.start(), n.end(), matches!(n, Numeric::Range(..)))),
+                    );
+                }
+                if let Some(letters) = letters {
+                    write_values(
+                        &mut body,
+                        letters
+                            .iter()
+                            .map(|l| (l.start(), l.end(), matches!(l, Alphabetic::Range(..)))),
+                    );
+                }
+            }
+        }
+
+        let mut out = Vec::with_capacity(MAGIC.len() + 1 + strings.len + body.len());
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        write_varint(&mut out, strings.order.len() as u64);
+        for s in &strings.order {
+            write_varint(&mut out, s.len() as u64);
+            out.extend_from_slice(s.as_bytes());
+        }
+        out.extend_from_slice(&body);
+        out
+    }
+
+    /// Deserialise from the compact binary layout, borrowing strings from `bytes`. See the [module docs](crate::binary).
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, Error> {
+        let mut reader = Reader { bytes, pos: 0 };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(Error::InvalidMagic);
+        }
+        let version = reader.take(1)?[0];
+        if version == 0 || version > VERSION {
+            return Err(Error::UnsupportedVersion(version));
+        }
+
+        let count = reader.len()?;
+        let mut strings = Vec::with_capacity(count.min(bytes.len()));
+        for i in 0..count {
+            let len = reader.len()?;
+            let s = core::str::from_utf8(reader.take(len)?).map_err(|_| Error::InvalidString(i))?;
+            strings.push(s);
+        }
+        let string = |index: u64| {
+            usize::try_from(index)
+                .ok()
+                .and_then(|i| strings.get(i).copied())
+                .ok_or(Error::InvalidIndex(index))
+        };
+        let optional = |index: u64| match index {
+            0 => Ok(None),
+            index => string(index - 1).map(Some),
+        };
+
+        let preamble = optional(reader.varint()?)?;
+        let postamble = optional(reader.varint()?)?;
+        let mut groups = Vec::new();
+        for _ in 0..reader.len()? {
+            let prefix = string(reader.varint()?)?;
+            let mut suffixes = Vec::new();
+            for _ in 0..reader.len()? {
+                let suffix = string(reader.varint()?)?;
+                let blocks = reader.take(1)?[0];
+                let numerics = match blocks & NUMERICS != 0 {
+                    true => Some(reader.values(|start, end| match end {
+                        Some(end) => Numeric::Range(start, end),
+                        None => Numeric::Single(start),
+                    })?),
+                    false => None,
+                };
+                let letters = match blocks & LETTERS != 0 {
+                    true => Some(reader.values(|start, end| match end {
+                        Some(end) => Alphabetic::Range(start, end),
+                        None => Alphabetic::Single(start),
+                    })?),
+                    false => None,
+                };
+                suffixes.push(Suffix {
+                    suffix,
+                    numerics,
+                    letters,
+                });
+            }
+            groups.push(Group { prefix, suffixes });
+        }
+
+        match bytes.len() - reader.pos {
+            0 => Ok(Brex {
+                preamble,
+                groups,
+                postamble,
+            }),
+            trailing => Err(Error::TrailingBytes(trailing)),
+        }
+    }
+}
+
+/// The string table, in order of first use
+#[derive(Default)]
+struct Strings<'a> {
+    indexes: BTreeMap<&'a str, u64>,
+    order: Vec<&'a str>,
+    /// Total byte length of the strings
+    len: usize,
+}
+
+impl<'a> Strings<'a> {
+    fn index(&mut self, s: &'a str) -> u64 {
+        *self.indexes.entry(s).or_insert_with(|| {
+            self.order.push(s);
+            self.len += s.len();
+            (self.order.len() - 1) as u64
+        })
+    }
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Write numeric values as (start, end, whether it is a range)
+fn write_values(out: &mut Vec<u8>, values: impl ExactSizeIterator<Item = (u32, u32, bool)>) {
+    write_varint(out, values.len() as u64);
+    for (start, end, range) in values {
+        write_varint(out, u64::from(start) << 1 | u64::from(range));
+        if range {
+            let delta = i64::from(end) - i64::from(start);
+            write_varint(out, ((delta << 1) ^ (delta >> 63)) as u64);
+        }
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        let end = self.pos.checked_add(len).ok_or(Error::UnexpectedEof)?;
+        let taken = self.bytes.get(self.pos..end).ok_or(Error::UnexpectedEof)?;
+        self.pos = end;
+        Ok(taken)
+    }
+
+    fn varint(&mut self) -> Result<u64, Error> {
+        let start = self.pos;
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            let bits = u64::from(byte & 0x7f);
+            if bits << shift >> shift != bits {
+                return Err(Error::Overflow(start));
+            }
+            value |= bits << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(Error::Overflow(start))
+    }
+
+    /// A count or length, which can't exceed the remaining input, as each item takes at least a byte
+    fn len(&mut self) -> Result<usize, Error> {
+        let len = self.varint()?;
+        usize::try_from(len)
+            .ok()
+            .filter(|len| *len <= self.bytes.len() - self.pos)
+            .ok_or(Error::UnexpectedEof)
+    }
+
+    fn values<T>(&mut self, value: impl Fn(u32, Option<u32>) -> T) -> Result<Vec<T>, Error> {
+        let count = self.len()?;
+        let mut values = Vec::with_capacity(count);
+        for _ in 0..count {
+            let pos = self.pos;
+            let tagged = self.varint()?;
+            let start = u32::try_from(tagged >> 1).map_err(|_| Error::Overflow(pos))?;
+            let end = match tagged & 1 {
+                0 => None,
+                _ => {
+                    let pos = self.pos;
+                    let zigzag = self.varint()?;
+                    let delta = (zigzag >> 1) as i64 ^ -((zigzag & 1) as i64);
+                    let end = i64::from(start).checked_add(delta);
+                    Some(
+                        end.and_then(|end| u32::try_from(end).ok())
+                            .ok_or(Error::InvalidRange(pos))?,
+                    )
+                }
+            };
+            values.push(value(start, end));
+        }
+        Ok(values)
+    }
+}
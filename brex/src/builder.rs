@@ -0,0 +1,282 @@
+//! Building brex IR programmatically
+//!
+//! See [`Brex::builder()`]
+
+use alloc::{
+    string::{String, ToString as _},
+    vec,
+    vec::Vec,
+};
+
+use crate::{
+    Alphabetic, Brex, Group, Numeric, Suffix,
+    alphabet::{BREX_BLOCK, GROUP_BLOCK, GROUP_SUFFIX_SEP, NUMERIC_BLOCK},
+};
+
+/// Characters that may not appear in a preamble
+pub(crate) const PREAMBLE_RESERVED: &[char] = &[BREX_BLOCK.start];
+/// Characters that may not appear in a group prefix
+pub(crate) const PREFIX_RESERVED: &[char] = &[
+    BREX_BLOCK.start,
+    BREX_BLOCK.end,
+    GROUP_BLOCK.start,
+    GROUP_BLOCK.end,
+];
+/// Characters that may not appear in a suffix
+pub(crate) const SUFFIX_RESERVED: &[char] = &[
+    BREX_BLOCK.start,
+    BREX_BLOCK.end,
+    GROUP_SUFFIX_SEP,
+    NUMERIC_BLOCK.start,
+    NUMERIC_BLOCK.end,
+];
+/// Characters that may not appear in a postamble
+pub(crate) const POSTAMBLE_RESERVED: &[char] = &[GROUP_BLOCK.start];
+
+/// The part of a brex string a piece of text belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    /// Plaintext before the brex block
+    Preamble,
+    /// A group's prefix
+    Prefix,
+    /// A group's suffix
+    Suffix,
+    /// Plaintext after the brex block
+    Postamble,
+}
+
+impl core::fmt::Display for Part {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Part::Preamble => "preamble",
+            Part::Prefix => "prefix",
+            Part::Suffix => "suffix",
+            Part::Postamble => "postamble",
+        })
+    }
+}
+
+/// Error building a brex
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    #[error("{part} {text:?} contains reserved character {ch:?}")]
+    /// Text contains a character of the brex alphabet that would be misparsed
+    ReservedChar {
+        /// Where the text was going to be placed
+        part: Part,
+        /// The offending text
+        text: String,
+        /// The reserved character
+        ch: char,
+    },
+    #[error("suffix {0:?} was added before any group")]
+    /// A suffix was added before [`BrexBuilder::group()`] was called
+    NoGroup(String),
+    #[error("group {0:?} has no suffixes")]
+    /// A group was left without any suffixes
+    EmptyGroup(String),
+    #[error("group {0:?} contains an empty suffix")]
+    /// A suffix was empty
+    EmptySuffix(String),
+    #[error("suffix {0:?} has no numerics")]
+    /// A numeric suffix was given an empty list of numerics
+    EmptyNumerics(String),
+    #[error("numeric range {0}→{1} ends before it starts")]
+    /// A [`Numeric::Range`] whose end is before its start
+    InvalidRange(u32, u32),
+    #[error("suffix {0:?} has no letters")]
+    /// An alphabetic suffix was given an empty list of letter sequences
+    EmptyLetters(String),
+    #[error("letter range {0} ends before it starts")]
+    /// An [`Alphabetic::Range`] whose end is before its start
+    InvalidLetterRange(Alphabetic),
+    #[error("{part} {text:?} has leading or trailing whitespace")]
+    /// Text has whitespace at either end, which is ignored when parsing
+    Whitespace {
+        /// Where the text was going to be placed
+        part: Part,
+        /// The offending text
+        text: String,
+    },
+    #[error("a postamble requires at least one group")]
+    /// A postamble was given without any groups, which is not displayed
+    PostambleWithoutGroups,
+}
+
+/// Builder for a [`Brex`], validating that the result can be displayed & parsed back unambiguously.
+///
+/// Created via [`Brex::builder()`].
+///
+/// ```
+/// # use brex::{Brex, Numeric};
+/// let brex = Brex::builder()
+///     .preamble("aatrox")
+///     .group("_skins")
+///     .suffix("_root")
+///     .numbers("_skin", [Numeric::Range(0, 2)])
+///     .postamble(".bin")
+///     .build()
+///     .unwrap();
+/// assert_eq!(brex.to_string(), "aatrox❮_skins{_root,_skin{0→2}}❯.bin");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct BrexBuilder<'a> {
+    preamble: Option<&'a str>,
+    groups: Vec<Group<'a>>,
+    postamble: Option<&'a str>,
+    error: Option<Error>,
+}
+
+impl<'a> BrexBuilder<'a> {
+    /// Create an empty builder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the plaintext before the brex block
+    pub fn preamble(mut self, preamble: &'a str) -> Self {
+        self.preamble = Some(preamble);
+        self
+    }
+
+    /// Set the plaintext after the brex block
+    pub fn postamble(mut self, postamble: &'a str) -> Self {
+        self.postamble = Some(postamble);
+        self
+    }
+
+    /// Start a new group. Following calls to [`Self::suffix()`], [`Self::numbers()`] & [`Self::letters()`] add to this group.
+    pub fn group(mut self, prefix: &'a str) -> Self {
+        self.groups.push(Group {
+            prefix,
+            suffixes: vec![],
+        });
+        self
+    }
+
+    /// Add a plain suffix to the current group
+    pub fn suffix(self, suffix: &'a str) -> Self {
+        self.push_suffix(Suffix::simple(suffix))
+    }
+
+    /// Add a suffix with numerics to the current group
+    pub fn numbers(self, suffix: &'a str, numerics: impl IntoIterator<Item = Numeric>) -> Self {
+        self.push_suffix(Suffix::numeric(suffix, numerics.into_iter().collect()))
+    }
+
+    /// Add a suffix with letter sequences to the current group
+    pub fn letters(self, suffix: &'a str, letters: impl IntoIterator<Item = Alphabetic>) -> Self {
+        self.push_suffix(Suffix::alphabetic(suffix, letters.into_iter().collect()))
+    }
+
+    fn push_suffix(mut self, suffix: Suffix<'a>) -> Self {
+        match self.groups.last_mut() {
+            Some(group) => group.suffixes.push(suffix),
+            None => {
+                self.error
+                    .get_or_insert_with(|| Error::NoGroup(suffix.suffix.to_string()));
+            }
+        }
+        self
+    }
+
+    /// Validate & build the [`Brex`]
+    pub fn build(self) -> Result<Brex<'a>, Error> {
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+        // empty ambles are indistinguishable from missing ones once displayed
+        let preamble = self.preamble.filter(|s| !s.is_empty());
+        let postamble = self.postamble.filter(|s| !s.is_empty());
+
+        if let Some(preamble) = preamble {
+            check_reserved(Part::Preamble, preamble, PREAMBLE_RESERVED)?;
+        }
+        if let Some(postamble) = postamble {
+            if self.groups.is_empty() {
+                return Err(Error::PostambleWithoutGroups);
+            }
+            check_reserved(Part::Postamble, postamble, POSTAMBLE_RESERVED)?;
+        }
+
+        for Group { prefix, suffixes } in &self.groups {
+            check_reserved(Part::Prefix, prefix, PREFIX_RESERVED)?;
+            check_whitespace(Part::Prefix, prefix)?;
+            if suffixes.is_empty() {
+                return Err(Error::EmptyGroup(prefix.to_string()));
+            }
+            for Suffix {
+                suffix,
+                numerics,
+                letters,
+            } in suffixes
+            {
+                if suffix.is_empty() {
+                    return Err(Error::EmptySuffix(prefix.to_string()));
+                }
+                check_reserved(Part::Suffix, suffix, SUFFIX_RESERVED)?;
+                check_whitespace(Part::Suffix, suffix)?;
+                if let Some(numerics) = numerics {
+                    if numerics.is_empty() {
+                        return Err(Error::EmptyNumerics(suffix.to_string()));
+                    }
+                    for numeric in numerics {
+                        if let Numeric::Range(start, end) = *numeric
+                            && end < start
+                        {
+                            return Err(Error::InvalidRange(start, end));
+                        }
+                    }
+                }
+                if let Some(letters) = letters {
+                    if letters.is_empty() {
+                        return Err(Error::EmptyLetters(suffix.to_string()));
+                    }
+                    for alphabetic in letters {
+                        if let Alphabetic::Range(start, end) = *alphabetic
+                            && end < start
+                        {
+                            return Err(Error::InvalidLetterRange(*alphabetic));
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(Brex {
+            preamble,
+            groups: self.groups,
+            postamble,
+        })
+    }
+}
+
+/// Prefixes & suffixes may not start or end with whitespace, as the parser skips it
+fn check_whitespace(part: Part, text: &str) -> Result<(), Error> {
+    match text.trim() == text {
+        true => Ok(()),
+        false => Err(Error::Whitespace {
+            part,
+            text: text.to_string(),
+        }),
+    }
+}
+
+fn check_reserved(part: Part, text: &str, reserved: &[char]) -> Result<(), Error> {
+    match text.chars().find(|ch| reserved.contains(ch)) {
+        Some(ch) => Err(Error::ReservedChar {
+            part,
+            text: text.to_string(),
+            ch,
+        }),
+        None => Ok(()),
+    }
+}
+
+impl<'a> Brex<'a> {
+    /// Create a [`BrexBuilder`], for constructing a brex by hand.
+    pub fn builder() -> BrexBuilder<'a> {
+        BrexBuilder::new()
+    }
+}
@@ -0,0 +1,84 @@
+//! Brex over byte strings, for names that aren't valid UTF-8
+//!
+//! Names pulled straight out of binary files aren't always UTF-8. The models are generic over [`crate::Text`],
+//! so these names can be encoded, parsed & expanded as `[u8]` without a lossy conversion.
+//! The brex alphabet itself is still UTF-8 encoded.
+//!
+//! ```
+//! let name = b"ahri_skin_\xff1_skin_\xff2_skin_\xff3.bin";
+//! let encoded = brex::bytes::encode(name).unwrap();
+//! let expected = [&b"ahri"[..], "❮_skin{_".as_bytes(), b"\xff", "{1→3}}❯.bin".as_bytes()];
+//! assert_eq!(encoded, expected.concat());
+//! assert_eq!(brex::bytes::decode(&encoded).unwrap(), name);
+//! ```
+
+use alloc::vec::Vec;
+
+use crate::{
+    encode::{self, EncodeOptions, Fallback},
+    parse::{self, Parser},
+};
+
+/// A [`crate::Brex`] over bytes
+pub type Brex<'a> = crate::Brex<'a, [u8]>;
+/// A [`crate::Group`] over bytes
+pub type Group<'a> = crate::Group<'a, [u8]>;
+/// A [`crate::Suffix`] over bytes
+pub type Suffix<'a> = crate::Suffix<'a, [u8]>;
+/// An [`encode::Checked`] over bytes
+pub type Checked<'a> = encode::Checked<'a, [u8]>;
+
+/// Encode bytes as brex. See [`crate::Brex::encode_with()`].
+///
+/// With [`encode::Case::Lower`], the input must already be folded with [`encode::Case::fold_bytes()`].
+pub fn encode_brex<'a>(
+    input: &'a [u8],
+    options: &EncodeOptions,
+) -> Result<Brex<'a>, encode::Error> {
+    encode::encode(input, options)
+}
+
+/// Encode bytes as brex, verifying that it decodes back to the input. See [`crate::Brex::encode_checked_with()`].
+pub fn encode_brex_checked<'a>(
+    input: &'a [u8],
+    options: &EncodeOptions,
+) -> Result<Checked<'a>, encode::Error> {
+    encode::encode_checked(input, options)
+}
+
+/// Parse a brex byte string. See [`crate::Brex::parse()`].
+pub fn parse(input: &[u8]) -> Result<Brex<'_>, parse::Error> {
+    Parser::new().parse_bytes(input)
+}
+
+/// Encode bytes to a brex byte string. See [`crate::encode()`].
+pub fn encode(input: &[u8]) -> Result<Vec<u8>, encode::Error> {
+    encode_with(input, &EncodeOptions::default())
+}
+
+/// Encode bytes to a brex byte string with the given [`EncodeOptions`].
+/// The input is folded according to [`EncodeOptions::case`] first.
+pub fn encode_with(input: &[u8], options: &EncodeOptions) -> Result<Vec<u8>, encode::Error> {
+    Ok(encode_brex(&options.case.fold_bytes(input), options)?.to_text())
+}
+
+/// Encode bytes to a brex byte string, verifying that it decodes back to the input. See [`crate::encode_checked()`].
+pub fn encode_checked(input: &[u8]) -> Result<(Vec<u8>, Option<Fallback>), encode::Error> {
+    encode_checked_with(input, &EncodeOptions::default())
+}
+
+/// [`encode_checked()`], with the given [`EncodeOptions`].
+/// The input is folded according to [`EncodeOptions::case`] first.
+pub fn encode_checked_with(
+    input: &[u8],
+    options: &EncodeOptions,
+) -> Result<(Vec<u8>, Option<Fallback>), encode::Error> {
+    let input = options.case.fold_bytes(input);
+    let checked = encode_brex_checked(&input, options)?;
+    Ok((checked.brex.to_text(), checked.fallback))
+}
+
+/// Parse and expand a brex byte string. To decode many strings, see [`Parser::decode_bytes_into()`].
+pub fn decode(encoded: &[u8]) -> Result<Vec<u8>, parse::Error> {
+    Parser::new().decode_bytes(encoded)
+}
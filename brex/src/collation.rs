@@ -0,0 +1,105 @@
+//! League's ordering of the parts of concatenated names
+//!
+//! A brex expands in this order, so names built by concatenating parts must follow it too:
+//! - Group prefixes compare bytewise, except that a prefix sorts *after* any longer prefix it starts,
+//!   so `superfanvariant` < `superfan`. See [`cmp()`] & [`Key`].
+//! - Values in a numeric block are in the order of their text, so `1` < `10` < `2`,
+//!   and numbers sort before letter sequences (`9` < `a`). See [`Value`].
+//!
+//! ```
+//! use brex::collation::{self, Key, Value};
+//!
+//! let mut prefixes = ["_superfan", "_base", "_superfanvariant"];
+//! collation::sort(&mut prefixes);
+//! assert_eq!(prefixes, ["_base", "_superfanvariant", "_superfan"]);
+//! assert!(Key::new("_superfanvariant") < Key::new("_superfan"));
+//!
+//! let mut values = [Value::Letters(0), Value::Number(2), Value::Number(10)];
+//! values.sort();
+//! assert_eq!(values, [Value::Number(10), Value::Number(2), Value::Letters(0)]);
+//! ```
+
+use core::cmp::Ordering;
+
+use crate::util::{decimal, letters};
+
+/// Compare group prefixes. Bytewise, except that a prefix sorts after any longer prefix it starts.
+///
+/// e.g `"superfan"` > `"superfanvariant"`
+pub fn cmp<T: AsRef<[u8]> + ?Sized>(a: &T, b: &T) -> Ordering {
+    let (a, b) = (a.as_ref(), b.as_ref());
+    let len = a.len().min(b.len());
+    match a[..len].cmp(&b[..len]) {
+        Ordering::Equal => b.len().cmp(&a.len()),
+        order => order,
+    }
+}
+
+/// Sort group prefixes with [`cmp()`]
+pub fn sort<T: AsRef<[u8]>>(items: &mut [T]) {
+    items.sort_by(|a, b| cmp(a, b));
+}
+
+/// A sort key ordering text with [`cmp()`], e.g for use in a `BTreeMap`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key<'a>(pub &'a [u8]);
+
+impl<'a> Key<'a> {
+    /// The key of some text
+    pub fn new<T: AsRef<[u8]> + ?Sized>(text: &'a T) -> Self {
+        Self(text.as_ref())
+    }
+}
+
+impl Ord for Key<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp(self.0, other.0)
+    }
+}
+
+impl PartialOrd for Key<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Compare numbers by their decimal text
+///
+/// e.g `10` < `9`
+pub fn cmp_numbers(a: u32, b: u32) -> Ordering {
+    decimal(a, &mut [0; 10]).cmp(decimal(b, &mut [0; 10]))
+}
+
+/// Compare letter sequence indexes (see [`crate::Alphabetic`]) by their letters
+///
+/// e.g `aa` < `b`
+pub fn cmp_letters(a: u32, b: u32) -> Ordering {
+    letters(a, &mut [0; 7]).cmp(letters(b, &mut [0; 7]))
+}
+
+/// A value of a numeric block, ordered by its text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Value {
+    /// A number
+    Number(u32),
+    /// The index of a letter sequence, as in [`crate::Alphabetic`]
+    Letters(u32),
+}
+
+impl Ord for Value {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Value::Number(a), Value::Number(b)) => cmp_numbers(*a, *b),
+            (Value::Letters(a), Value::Letters(b)) => cmp_letters(*a, *b),
+            // digits sort before lowercase letters
+            (Value::Number(_), Value::Letters(_)) => Ordering::Less,
+            (Value::Letters(_), Value::Number(_)) => Ordering::Greater,
+        }
+    }
+}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
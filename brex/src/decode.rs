@@ -1,26 +1,69 @@
+use alloc::{
+    format,
+    string::{String, ToString as _},
+    vec,
+    vec::Vec,
+};
+
 use itertools::Itertools;
 
-use crate::{Brex, Group, Suffix, util::inverted_substr_sort};
+use crate::{
+    Brex, Group, Suffix, collation,
+    util::{decimal, letters},
+};
 
 impl<'a> Brex<'a> {
     /// Expand this brex to plaintext.
     ///
     /// See [`crate::decode()`] for a convenience wrapper that both parses & expands a brex string.
     pub fn expand(&self) -> String {
+        self.components().concat()
+    }
+
+    /// The pieces this brex expands to, in order: the preamble, each group prefix & suffix, then the postamble.
+    ///
+    /// ```
+    /// # use brex::Brex;
+    /// let brex = Brex::parse("ahri❮_skin{1,2}❯.bin").unwrap();
+    /// assert_eq!(brex.components(), ["ahri", "_skin1", "_skin2", ".bin"]);
+    /// assert_eq!(brex.components().concat(), brex.expand());
+    /// ```
+    pub fn components(&self) -> Vec<String> {
         let groups = self
             .groups
             .iter()
-            .sorted_unstable_by(|a, b| inverted_substr_sort(a.prefix, b.prefix))
-            .flat_map(|group| group.expand())
-            .collect::<Vec<_>>();
-        match (self.preamble, self.postamble) {
-            (Some(pre), Some(post)) => format!("{pre}{}{post}", groups.join("")),
-            (Some(pre), None) => format!("{pre}{}", groups.join("")),
-            (None, Some(post)) => format!("{}{post}", groups.join("")),
-            (None, None) => groups.join(""),
+            .sorted_by(|a, b| collation::cmp(a.prefix, b.prefix))
+            .flat_map(|group| group.expand());
+        self.preamble
+            .map(String::from)
+            .into_iter()
+            .chain(groups)
+            .chain(self.postamble.map(String::from))
+            .collect()
+    }
+}
+
+impl Brex<'_, [u8]> {
+    /// Expand this brex to plaintext bytes.
+    ///
+    /// See [`crate::bytes::decode()`] for a convenience wrapper that both parses & expands a brex byte string.
+    pub fn expand(&self) -> Vec<u8> {
+        let mut out = self.preamble.unwrap_or_default().to_vec();
+        for group in self
+            .groups
+            .iter()
+            .sorted_by(|a, b| collation::cmp(a.prefix, b.prefix))
+        {
+            for suffix in group.suffixes.iter().flat_map(|suffix| suffix.expand()) {
+                out.extend_from_slice(group.prefix);
+                out.extend_from_slice(&suffix);
+            }
         }
+        out.extend_from_slice(self.postamble.unwrap_or_default());
+        out
     }
 }
+
 impl<'a> Group<'a> {
     fn expand(&self) -> impl Iterator<Item = String> {
         self.suffixes
@@ -31,16 +74,52 @@ impl<'a> Group<'a> {
     }
 }
 
+impl<T: ?Sized> Suffix<'_, T> {
+    /// The numbers & letter sequence indexes in this suffix's numeric block, each in string order
+    fn values(&self) -> (impl Iterator<Item = u32>, impl Iterator<Item = u32>) {
+        let numbers = self
+            .numerics
+            .iter()
+            .flatten()
+            .flat_map(|numeric| numeric.start()..=numeric.end())
+            .sorted_unstable_by(|a, b| collation::cmp_numbers(*a, *b));
+        let sequences = self
+            .letters
+            .iter()
+            .flatten()
+            .flat_map(|alphabetic| alphabetic.start()..=alphabetic.end())
+            .sorted_unstable_by(|a, b| collation::cmp_letters(*a, *b));
+        (numbers, sequences)
+    }
+}
+
 impl<'a> Suffix<'a> {
+    /// Expand to each suffix in string order. Numbers always sort before letters.
     fn expand(&self) -> Vec<String> {
-        match &self.numerics {
-            Some(numerics) => numerics
-                .iter()
-                .flat_map(|numeric| numeric.start()..=numeric.end())
-                .map(|num| format!("{}{num}", self.suffix))
-                .sorted()
-                .collect(),
-            None => vec![self.suffix.to_string()],
+        if !self.has_block() {
+            return vec![self.suffix.to_string()];
+        }
+        let (numbers, sequences) = self.values();
+        numbers
+            .map(|num| format!("{}{num}", self.suffix))
+            .chain(sequences.map(|index| format!("{}{}", self.suffix, letters(index, &mut [0; 7]))))
+            .collect()
+    }
+}
+
+impl Suffix<'_, [u8]> {
+    /// Expand to each suffix in byte order. Numbers always sort before letters.
+    fn expand(&self) -> Vec<Vec<u8>> {
+        if !self.has_block() {
+            return vec![self.suffix.to_vec()];
         }
+        let (numbers, sequences) = self.values();
+        numbers
+            .map(|num| [self.suffix, decimal(num, &mut [0; 10])].concat())
+            .chain(
+                sequences
+                    .map(|index| [self.suffix, letters(index, &mut [0; 7]).as_bytes()].concat()),
+            )
+            .collect()
     }
 }
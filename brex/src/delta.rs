@@ -0,0 +1,472 @@
+//! Delta encoding of brex corpora
+//!
+//! Consecutive lines of a corpus are often near-duplicates. A [`DeltaEncoder`] writes each line either as a full brex
+//! string, or as a delta against one of the previous lines, adding & removing groups, suffixes & numeric values.
+//! A [`DeltaDecoder`] reads the lines back in the same order.
+//!
+//! A delta line starts with [`DELTA`], followed by how many lines back its base is (omitted for the previous line),
+//! followed by its changes, in a brex block:
+//! ```text
+//! aatrox❮_skins{_root,_skin{0→32}}❯.bin
+//! Δ❮~_skins{-_skin{30→32}}❯
+//! Δ2❮~_skins{-_skin{21→29}}❯
+//! ```
+//! - `+prefix{suffixes}` adds a group
+//! - `-prefix{}` removes a group
+//! - `~prefix{±suffix,…}` adds (`+`) or removes (`-`) suffixes, numeric values & letter sequences from a group
+//!
+//! Deltas require the same preamble & postamble as their base, and are only used if they decode back to the line.
+//! A full line that happens to start with [`DELTA`] is escaped as `Δ0`.
+
+use alloc::{
+    collections::{BTreeMap, VecDeque},
+    string::{String, ToString as _},
+    vec::Vec,
+};
+use core::fmt::Write as _;
+
+use crate::{
+    Alphabetic, Brex, Group, Numeric, Suffix,
+    alphabet::{
+        BREX_BLOCK, GROUP_BLOCK, GROUP_SUFFIX_SEP, NUMERIC_BLOCK, NUMERIC_LIST_SEP,
+        NUMERIC_RANGE_DELIM,
+    },
+    diff::{Diff, Side, Values},
+    encode::{self, EncodeOptions},
+    models::write_block,
+    parse,
+};
+
+/// Marks a delta line
+pub const DELTA: char = 'Δ';
+
+/// Error delta encoding or decoding
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    /// Error encoding a full line
+    Encode(#[from] encode::Error),
+    #[error(transparent)]
+    /// Error parsing a full line
+    Parse(#[from] parse::Error),
+    #[error("delta refers {0} lines back, beyond the lines decoded so far")]
+    /// A delta's base is not in the window of previous lines
+    MissingBase(usize),
+    #[error("invalid delta at byte {0}")]
+    /// A delta line is malformed
+    InvalidDelta(usize),
+}
+
+/// Options for [`DeltaEncoder`]
+#[derive(Debug, Clone)]
+pub struct DeltaOptions {
+    /// Options used to encode full lines. [`EncodeOptions::optimise_for`] also picks between full lines & deltas.
+    pub encode: EncodeOptions,
+    /// How many previous lines to consider as a base
+    pub window: usize,
+}
+
+impl Default for DeltaOptions {
+    fn default() -> Self {
+        Self {
+            encode: EncodeOptions::default(),
+            window: 8,
+        }
+    }
+}
+
+/// Suffix text & whether it has a numeric block, mapped to its values
+type Suffixes = BTreeMap<(String, bool), Values>;
+
+/// The components of a decoded line, which later deltas apply to
+#[derive(Debug, Clone, Default)]
+struct Components {
+    preamble: String,
+    /// Each group's suffixes, by prefix
+    groups: BTreeMap<String, Suffixes>,
+    postamble: String,
+}
+
+impl Components {
+    fn from_brex(brex: &Brex) -> Self {
+        let mut components = Self {
+            preamble: brex.preamble.unwrap_or_default().to_string(),
+            groups: BTreeMap::new(),
+            postamble: brex.postamble.unwrap_or_default().to_string(),
+        };
+        for group in &brex.groups {
+            let suffixes = components
+                .groups
+                .entry(group.prefix.to_string())
+                .or_default();
+            for suffix in &group.suffixes {
+                suffixes
+                    .entry((suffix.suffix.to_string(), suffix.has_block()))
+                    .or_default()
+                    .add(suffix.numerics.as_deref(), suffix.letters.as_deref());
+            }
+        }
+        components
+    }
+
+    /// A brex expanding to these components. Plain suffixes come before ones with numeric blocks.
+    fn to_brex(&self) -> Brex<'_> {
+        let groups = self
+            .groups
+            .iter()
+            .map(|(prefix, suffixes)| {
+                let plain = suffixes
+                    .keys()
+                    .filter(|(_, block)| !block)
+                    .map(|(suffix, _)| Suffix::simple(suffix.as_str()));
+                let numeric = suffixes.iter().filter(|((_, block), _)| *block).map(
+                    |((suffix, _), values)| Suffix {
+                        suffix: suffix.as_str(),
+                        numerics: values.numerics(),
+                        letters: values.alphabetics(),
+                    },
+                );
+                Group {
+                    prefix: prefix.as_str(),
+                    suffixes: plain.chain(numeric).collect(),
+                }
+            })
+            .collect();
+        Brex {
+            preamble: Some(self.preamble.as_str()).filter(|s| !s.is_empty()),
+            groups,
+            postamble: Some(self.postamble.as_str()).filter(|s| !s.is_empty()),
+        }
+    }
+
+    fn apply(&self, diff: &Diff) -> Self {
+        let mut components = self.clone();
+        for group in &diff.groups {
+            match group.side {
+                Some(Side::Removed) => {
+                    components.groups.remove(group.prefix);
+                    continue;
+                }
+                Some(Side::Added) | None => {}
+            }
+            let suffixes = components
+                .groups
+                .entry(group.prefix.to_string())
+                .or_default();
+            for suffix in &group.suffixes {
+                let key = (
+                    suffix.suffix.to_string(),
+                    suffix.numerics.is_some() || suffix.letters.is_some(),
+                );
+                let (numerics, letters) = (suffix.numerics.as_deref(), suffix.letters.as_deref());
+                match suffix.side {
+                    Side::Added => suffixes.entry(key).or_default().add(numerics, letters),
+                    Side::Removed => {
+                        let Some(values) = suffixes.get_mut(&key) else {
+                            continue;
+                        };
+                        *values = values.subtract(&Values::of(numerics, letters));
+                        if values.is_empty() {
+                            suffixes.remove(&key);
+                        }
+                    }
+                }
+            }
+            if suffixes.is_empty() {
+                components.groups.remove(group.prefix);
+            }
+        }
+        components
+    }
+}
+
+/// Encodes lines as full brex or deltas against previous lines. See the [module docs](self).
+#[derive(Debug, Clone, Default)]
+pub struct DeltaEncoder {
+    options: DeltaOptions,
+    /// Previous lines, most recent first
+    window: VecDeque<Components>,
+}
+
+impl DeltaEncoder {
+    /// Create an encoder with no previous lines
+    pub fn new(options: DeltaOptions) -> Self {
+        Self {
+            options,
+            window: VecDeque::new(),
+        }
+    }
+
+    /// Encode the next line, folded according to [`EncodeOptions::case`]
+    pub fn encode(&mut self, line: &str) -> Result<String, Error> {
+        let line = self.options.encode.case.fold(line);
+        let line = line.as_ref();
+        let brex = Brex::encode_checked_with(line, &self.options.encode)?.brex;
+        let mut best = brex.to_string();
+        if best.starts_with(DELTA) {
+            best.insert_str(0, "Δ0");
+        }
+        let mut components = Components::from_brex(&brex);
+
+        let length = self.options.encode.optimise_for;
+        for (i, base) in self.window.iter().enumerate() {
+            let base_brex = base.to_brex();
+            let diff = base_brex.diff(&brex);
+            if diff.preamble.is_some() || diff.postamble.is_some() {
+                continue;
+            }
+            let delta = write_delta(i + 1, &diff);
+            if length.of(&delta) >= length.of(&best) {
+                continue;
+            }
+            let applied = base.apply(&diff);
+            if applied.to_brex().expand() == line {
+                best = delta;
+                components = applied;
+            }
+        }
+
+        push(&mut self.window, components, self.options.window);
+        Ok(best)
+    }
+}
+
+/// Decodes lines written by a [`DeltaEncoder`]. See the [module docs](self).
+#[derive(Debug, Clone)]
+pub struct DeltaDecoder {
+    window_size: usize,
+    /// Previous lines, most recent first
+    window: VecDeque<Components>,
+}
+
+impl Default for DeltaDecoder {
+    fn default() -> Self {
+        Self::new(DeltaOptions::default().window)
+    }
+}
+
+impl DeltaDecoder {
+    /// Create a decoder with no previous lines, keeping `window` previous lines as bases.
+    ///
+    /// This must be at least the [`DeltaOptions::window`] the lines were encoded with.
+    pub fn new(window: usize) -> Self {
+        Self {
+            window_size: window,
+            window: VecDeque::new(),
+        }
+    }
+
+    /// Decode the next line
+    pub fn decode(&mut self, line: &str) -> Result<String, Error> {
+        let (decoded, components) = match line.strip_prefix(DELTA) {
+            Some(delta) => {
+                let digits = delta.bytes().take_while(u8::is_ascii_digit).count();
+                let back = match digits {
+                    0 => 1,
+                    _ => delta[..digits]
+                        .parse()
+                        .map_err(|_| Error::InvalidDelta(DELTA.len_utf8()))?,
+                };
+                let rest = &delta[digits..];
+                match back {
+                    0 => full(rest)?,
+                    back => {
+                        let base = self.window.get(back - 1).ok_or(Error::MissingBase(back))?;
+                        let offset = line.len() - rest.len();
+                        let diff = read_delta(rest)
+                            .map_err(|position| Error::InvalidDelta(offset + position))?;
+                        let components = base.apply(&diff);
+                        (components.to_brex().expand(), components)
+                    }
+                }
+            }
+            None => full(line)?,
+        };
+        push(&mut self.window, components, self.window_size);
+        Ok(decoded)
+    }
+}
+
+fn full(line: &str) -> Result<(String, Components), Error> {
+    let brex = Brex::parse(line)?;
+    Ok((brex.expand(), Components::from_brex(&brex)))
+}
+
+fn push(window: &mut VecDeque<Components>, components: Components, size: usize) {
+    window.push_front(components);
+    window.truncate(size);
+}
+
+fn write_delta(back: usize, diff: &Diff) -> String {
+    let mut out = String::new();
+    out.push(DELTA);
+    if back != 1 {
+        let _ = write!(out, "{back}");
+    }
+    out.push(BREX_BLOCK.start);
+    for group in &diff.groups {
+        out.push(match group.side {
+            Some(Side::Added) => '+',
+            Some(Side::Removed) => '-',
+            None => '~',
+        });
+        out.push_str(group.prefix);
+        out.push(GROUP_BLOCK.start);
+        if group.side != Some(Side::Removed) {
+            for (i, suffix) in group.suffixes.iter().enumerate() {
+                if i > 0 {
+                    out.push(GROUP_SUFFIX_SEP);
+                }
+                if group.side.is_none() {
+                    out.push(match suffix.side {
+                        Side::Added => '+',
+                        Side::Removed => '-',
+                    });
+                }
+                out.push_str(suffix.suffix);
+                // writing to a `String` is infallible
+                let _ = write_block(
+                    &mut out,
+                    suffix.numerics.as_deref(),
+                    suffix.letters.as_deref(),
+                );
+            }
+        }
+        out.push(GROUP_BLOCK.end);
+    }
+    out.push(BREX_BLOCK.end);
+    out
+}
+
+/// Read the changes of a delta line, returning the byte position of any error
+fn read_delta(input: &str) -> Result<Diff<'_>, usize> {
+    use crate::diff::{GroupDiff, SuffixDiff};
+
+    let mut pos = 0;
+    let expect = |pos: &mut usize, ch: char| match input[*pos..].starts_with(ch) {
+        true => {
+            *pos += ch.len_utf8();
+            Ok(())
+        }
+        false => Err(*pos),
+    };
+    let sign = |pos: &mut usize| {
+        let side = match input[*pos..].chars().next() {
+            Some('+') => Some(Side::Added),
+            Some('-') => Some(Side::Removed),
+            Some('~') => None,
+            _ => return Err(*pos),
+        };
+        *pos += 1;
+        Ok(side)
+    };
+
+    expect(&mut pos, BREX_BLOCK.start)?;
+    let mut groups = Vec::new();
+    while !input[pos..].starts_with(BREX_BLOCK.end) {
+        let side = sign(&mut pos)?;
+        let len = input[pos..].find(GROUP_BLOCK.start).ok_or(pos)?;
+        let prefix = &input[pos..pos + len];
+        pos += len + GROUP_BLOCK.start.len_utf8();
+
+        let mut suffixes = Vec::new();
+        while !input[pos..].starts_with(GROUP_BLOCK.end) {
+            if !suffixes.is_empty() {
+                expect(&mut pos, GROUP_SUFFIX_SEP)?;
+            }
+            let suffix_side = match side {
+                None => sign(&mut pos)?.ok_or(pos - 1)?,
+                Some(side) => side,
+            };
+            let len = input[pos..]
+                .find([GROUP_SUFFIX_SEP, NUMERIC_BLOCK.start, NUMERIC_BLOCK.end])
+                .ok_or(pos)?;
+            let suffix = &input[pos..pos + len];
+            pos += len;
+            let (numerics, letters) = match input[pos..].starts_with(NUMERIC_BLOCK.start) {
+                true => read_block(input, &mut pos)?,
+                false => (None, None),
+            };
+            suffixes.push(SuffixDiff {
+                side: suffix_side,
+                suffix,
+                numerics,
+                letters,
+            });
+        }
+        pos += GROUP_BLOCK.end.len_utf8();
+        groups.push(GroupDiff {
+            prefix,
+            side,
+            suffixes,
+        });
+    }
+    expect(&mut pos, BREX_BLOCK.end)?;
+    if pos != input.len() {
+        return Err(pos);
+    }
+    Ok(Diff {
+        preamble: None,
+        groups,
+        postamble: None,
+    })
+}
+
+/// Numbers & letter sequences of a numeric block, either of which may be absent
+type Block = (Option<Vec<Numeric>>, Option<Vec<Alphabetic>>);
+
+fn read_block(input: &str, pos: &mut usize) -> Result<Block, usize> {
+    // a number, or the index of a letter sequence
+    let value = |pos: &mut usize| {
+        let digits = input[*pos..].bytes().take_while(u8::is_ascii_digit).count();
+        if digits > 0 {
+            let value = input[*pos..*pos + digits]
+                .parse::<u32>()
+                .map_err(|_| *pos)?;
+            *pos += digits;
+            return Ok((true, value));
+        }
+        let len = input[*pos..]
+            .bytes()
+            .take_while(u8::is_ascii_lowercase)
+            .count();
+        let value = Alphabetic::index(&input[*pos..*pos + len]).ok_or(*pos)?;
+        *pos += len;
+        Ok::<_, usize>((false, value))
+    };
+    *pos += NUMERIC_BLOCK.start.len_utf8();
+    let mut numerics = Vec::new();
+    let mut letters = Vec::new();
+    loop {
+        let (numeric, start) = value(pos)?;
+        let end = match input[*pos..].starts_with(NUMERIC_RANGE_DELIM) {
+            true => {
+                *pos += NUMERIC_RANGE_DELIM.len_utf8();
+                let at = *pos;
+                let (end_numeric, end) = value(pos)?;
+                if end_numeric != numeric {
+                    return Err(at);
+                }
+                Some(end)
+            }
+            false => None,
+        };
+        match (numeric, end) {
+            (true, Some(end)) => numerics.push(Numeric::Range(start, end)),
+            (true, None) => numerics.push(Numeric::Single(start)),
+            (false, Some(end)) => letters.push(Alphabetic::Range(start, end)),
+            (false, None) => letters.push(Alphabetic::Single(start)),
+        }
+        match input[*pos..].chars().next() {
+            Some(NUMERIC_LIST_SEP) => *pos += NUMERIC_LIST_SEP.len_utf8(),
+            Some(ch) if ch == NUMERIC_BLOCK.end => {
+                *pos += ch.len_utf8();
+                return Ok((
+                    (!numerics.is_empty()).then_some(numerics),
+                    (!letters.is_empty()).then_some(letters),
+                ));
+            }
+            _ => return Err(*pos),
+        }
+    }
+}
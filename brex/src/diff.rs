@@ -0,0 +1,355 @@
+//! Structural diffs between brex strings
+//!
+//! Compares the sets of groups, suffixes & numeric values of two brex, rather than their expanded text.
+//! Numeric values are compared by what they expand to, so `_skin{0→2}` & `_skin{0,1,2}` are considered equal, as are `_{a→c}` & `_{a,b,c}`.
+//! The order of suffixes within a group is not compared, so an empty diff doesn't mean both brex expand to the same text.
+//!
+//! See [`Brex::diff()`]
+
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::fmt::{self, Write as _};
+
+use crate::{
+    Alphabetic, Brex, Numeric,
+    alphabet::{GROUP_BLOCK, GROUP_SUFFIX_SEP},
+    models::write_block,
+};
+
+/// Which side of a diff something is on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    /// Only in the new brex
+    Added,
+    /// Only in the old brex
+    Removed,
+}
+
+impl Side {
+    fn sign(&self) -> char {
+        match self {
+            Side::Added => '+',
+            Side::Removed => '-',
+        }
+    }
+}
+
+/// A preamble or postamble that differs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Changed<'a> {
+    /// The old text
+    pub from: Option<&'a str>,
+    /// The new text
+    pub to: Option<&'a str>,
+}
+
+/// A suffix, or some of its numeric values, only present on one side
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuffixDiff<'a> {
+    /// Which side the suffix is on
+    pub side: Side,
+    /// The suffix text
+    pub suffix: &'a str,
+    /// The numeric values only on [`Self::side`], or [`None`] for a plain suffix or if none differ
+    pub numerics: Option<Vec<Numeric>>,
+    /// The letter sequences only on [`Self::side`], or [`None`] for a plain suffix or if none differ
+    pub letters: Option<Vec<Alphabetic>>,
+}
+
+/// Differences within a group
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupDiff<'a> {
+    /// The group's prefix
+    pub prefix: &'a str,
+    /// The side the whole group is on, or [`None`] if it is on both sides with differing suffixes
+    pub side: Option<Side>,
+    /// The differing suffixes
+    pub suffixes: Vec<SuffixDiff<'a>>,
+}
+
+/// Structural differences between two brex. Created via [`Brex::diff()`].
+///
+/// Displays one line per change, e.g `_skins{+_skin{30→32}}`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Diff<'a> {
+    /// The preamble, if it changed
+    pub preamble: Option<Changed<'a>>,
+    /// Groups that were added, removed or changed, ordered by prefix
+    pub groups: Vec<GroupDiff<'a>>,
+    /// The postamble, if it changed
+    pub postamble: Option<Changed<'a>>,
+}
+
+impl Diff<'_> {
+    /// Whether both brex have the same set of components: the same preamble & postamble,
+    /// and the same suffixes & numeric values in each group, in any order
+    pub fn is_empty(&self) -> bool {
+        self.preamble.is_none() && self.groups.is_empty() && self.postamble.is_none()
+    }
+}
+
+/// Suffix text & whether it has a numeric block, mapped to its values
+type Suffixes<'a> = BTreeMap<(&'a str, bool), Values>;
+
+/// The normalised numeric & letter ranges of a suffix's numeric block
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Values {
+    pub numbers: Vec<(u32, u32)>,
+    pub letters: Vec<(u32, u32)>,
+}
+
+impl Values {
+    pub(crate) fn of(numerics: Option<&[Numeric]>, letters: Option<&[Alphabetic]>) -> Self {
+        let mut values = Self::default();
+        values.add(numerics, letters);
+        values
+    }
+
+    pub(crate) fn add(&mut self, numerics: Option<&[Numeric]>, letters: Option<&[Alphabetic]>) {
+        self.numbers.extend(
+            numerics
+                .into_iter()
+                .flatten()
+                .map(|numeric| (numeric.start(), numeric.end())),
+        );
+        self.numbers = normalise(core::mem::take(&mut self.numbers));
+        self.letters.extend(
+            letters
+                .into_iter()
+                .flatten()
+                .map(|alphabetic| (alphabetic.start(), alphabetic.end())),
+        );
+        self.letters = normalise(core::mem::take(&mut self.letters));
+    }
+
+    /// Values in `self` that are not in `other`
+    pub(crate) fn subtract(&self, other: &Values) -> Self {
+        Self {
+            numbers: subtract(&self.numbers, &other.numbers),
+            letters: subtract(&self.letters, &other.letters),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.numbers.is_empty() && self.letters.is_empty()
+    }
+
+    /// The numbers as [`Numeric`]s, or [`None`] if there are none
+    pub(crate) fn numerics(&self) -> Option<Vec<Numeric>> {
+        (!self.numbers.is_empty()).then(|| {
+            self.numbers
+                .iter()
+                .map(|&(start, end)| match start == end {
+                    true => Numeric::Single(start),
+                    false => Numeric::Range(start, end),
+                })
+                .collect()
+        })
+    }
+
+    /// The letters as [`Alphabetic`]s, or [`None`] if there are none
+    pub(crate) fn alphabetics(&self) -> Option<Vec<Alphabetic>> {
+        (!self.letters.is_empty()).then(|| {
+            self.letters
+                .iter()
+                .map(|&(start, end)| match start == end {
+                    true => Alphabetic::Single(start),
+                    false => Alphabetic::Range(start, end),
+                })
+                .collect()
+        })
+    }
+}
+
+impl<'a> Brex<'a> {
+    /// Structurally compare this brex (the old side) against `other` (the new side).
+    pub fn diff(&self, other: &Brex<'a>) -> Diff<'a> {
+        let changed = |from: Option<&'a str>, to: Option<&'a str>| {
+            (from != to).then_some(Changed { from, to })
+        };
+
+        let old = groups(self);
+        let new = groups(other);
+        let mut prefixes = old.keys().chain(new.keys()).copied().collect::<Vec<_>>();
+        prefixes.sort_unstable();
+        prefixes.dedup();
+
+        let groups = prefixes
+            .into_iter()
+            .filter_map(|prefix| {
+                let (side, suffixes) = match (old.get(prefix), new.get(prefix)) {
+                    (Some(old), Some(new)) => (
+                        None,
+                        diff_suffixes(old, new, Side::Removed)
+                            .chain(diff_suffixes(new, old, Side::Added))
+                            .collect::<Vec<_>>(),
+                    ),
+                    (Some(old), None) => (
+                        Some(Side::Removed),
+                        diff_suffixes(old, &BTreeMap::new(), Side::Removed).collect(),
+                    ),
+                    (None, Some(new)) => (
+                        Some(Side::Added),
+                        diff_suffixes(new, &BTreeMap::new(), Side::Added).collect(),
+                    ),
+                    (None, None) => unreachable!("prefix came from one of the groups"),
+                };
+                (!suffixes.is_empty()).then_some(GroupDiff {
+                    prefix,
+                    side,
+                    suffixes,
+                })
+            })
+            .collect();
+
+        Diff {
+            preamble: changed(self.preamble, other.preamble),
+            groups,
+            postamble: changed(self.postamble, other.postamble),
+        }
+    }
+}
+
+/// Collect each group's suffixes, merging groups that share a prefix
+fn groups<'a>(brex: &Brex<'a>) -> BTreeMap<&'a str, Suffixes<'a>> {
+    let mut groups = BTreeMap::<_, Suffixes>::new();
+    for group in &brex.groups {
+        let suffixes = groups.entry(group.prefix).or_default();
+        for suffix in &group.suffixes {
+            suffixes
+                .entry((suffix.suffix, suffix.has_block()))
+                .or_default()
+                .add(suffix.numerics.as_deref(), suffix.letters.as_deref());
+        }
+    }
+    groups
+}
+
+/// Suffixes & numeric values in `a` but not in `b`
+fn diff_suffixes<'a>(
+    a: &Suffixes<'a>,
+    b: &Suffixes<'a>,
+    side: Side,
+) -> impl Iterator<Item = SuffixDiff<'a>> {
+    a.iter().filter_map(move |(&(suffix, block), values)| {
+        let values = match (block, b.get(&(suffix, block))) {
+            (false, Some(_)) => return None,
+            (false, None) => Values::default(),
+            (true, other) => {
+                let values = values.subtract(other.unwrap_or(&Values::default()));
+                if values.is_empty() {
+                    return None;
+                }
+                values
+            }
+        };
+        Some(SuffixDiff {
+            side,
+            suffix,
+            numerics: values.numerics(),
+            letters: values.alphabetics(),
+        })
+    })
+}
+
+/// Sort & merge overlapping or adjacent ranges
+pub(crate) fn normalise(mut ranges: Vec<(u32, u32)>) -> Vec<(u32, u32)> {
+    ranges.retain(|(start, end)| start <= end);
+    ranges.sort_unstable();
+    let mut merged = Vec::<(u32, u32)>::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if u64::from(start) <= u64::from(last.1) + 1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// Values in the normalised ranges `a` that are not in the normalised ranges `b`
+pub(crate) fn subtract(a: &[(u32, u32)], b: &[(u32, u32)]) -> Vec<(u32, u32)> {
+    let mut out = Vec::new();
+    let mut b = b.iter().peekable();
+    for &(start, end) in a {
+        let mut start = u64::from(start);
+        let end = u64::from(end);
+        // skip ranges entirely before this one
+        while b.next_if(|(_, b_end)| u64::from(*b_end) < start).is_some() {}
+        for &(b_start, b_end) in b.clone() {
+            let (b_start, b_end) = (u64::from(b_start), u64::from(b_end));
+            if b_start > end {
+                break;
+            }
+            if b_start > start {
+                out.push((start, b_start - 1));
+            }
+            start = b_end + 1;
+            if start > end {
+                break;
+            }
+        }
+        if start <= end {
+            out.push((start, end));
+        }
+    }
+    // every value came from a `u32`
+    out.into_iter()
+        .map(|(start, end)| (start as u32, end as u32))
+        .collect()
+}
+
+impl fmt::Display for SuffixDiff<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // the sign is left to the group when the whole group is added or removed
+        if !f.alternate() {
+            f.write_char(self.side.sign())?;
+        }
+        f.write_str(self.suffix)?;
+        write_block(f, self.numerics.as_deref(), self.letters.as_deref())
+    }
+}
+
+impl fmt::Display for GroupDiff<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(side) = self.side {
+            f.write_char(side.sign())?;
+        }
+        f.write_str(self.prefix)?;
+        f.write_char(GROUP_BLOCK.start)?;
+        for (i, suffix) in self.suffixes.iter().enumerate() {
+            if i > 0 {
+                f.write_char(GROUP_SUFFIX_SEP)?;
+            }
+            match self.side {
+                Some(_) => write!(f, "{suffix:#}")?,
+                None => write!(f, "{suffix}")?,
+            }
+        }
+        f.write_char(GROUP_BLOCK.end)
+    }
+}
+
+impl fmt::Display for Changed<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} → {:?}",
+            self.from.unwrap_or_default(),
+            self.to.unwrap_or_default()
+        )
+    }
+}
+
+impl fmt::Display for Diff<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(preamble) = &self.preamble {
+            writeln!(f, "preamble: {preamble}")?;
+        }
+        for group in &self.groups {
+            writeln!(f, "{group}")?;
+        }
+        if let Some(postamble) = &self.postamble {
+            writeln!(f, "postamble: {postamble}")?;
+        }
+        Ok(())
+    }
+}
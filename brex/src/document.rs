@@ -0,0 +1,514 @@
+//! Brex documents: files of brex entries, with a header, comments & metadata
+//!
+//! ```text
+//! #!brex version=1 dialect=plain delimiter=line
+//! # comments apply to the entry after them
+//! @patch=14.1
+//! aatrox❮_skins{_root,_skin{0→32}}❯.bin
+//! ahri❮_skins{_root,_skin{0→84}}❯.bin
+//! ```
+//! - The header is optional, and defaults to [`Header::default()`]
+//! - Lines starting with `#` are comments
+//! - Lines of the form `@key=value` are metadata for the next entry
+//! - Entries that would start with `#`, `@` or `\` are escaped with a leading `\` (see [`escape()`])
+//!
+//! With [`Delimiter::Blank`], entries are separated by blank lines, and may span multiple lines,
+//! so plain entries are written in the pretty form (see [`Brex`]'s `Display`).
+//!
+//! See [`BrexDocument`]
+
+use alloc::{
+    borrow::Cow,
+    format,
+    string::{String, ToString as _},
+    vec::Vec,
+};
+use core::fmt::{self, Write as _};
+
+use crate::{
+    Brex,
+    delta::{self, DeltaDecoder, DeltaEncoder, DeltaOptions},
+    encode::{self, EncodeOptions, Fallback},
+    parse::{self, Parser},
+};
+
+/// Starts a document header
+pub const HEADER: &str = "#!brex";
+/// The latest document version
+pub const VERSION: u32 = 1;
+
+/// Error reading or writing a brex document
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("invalid header - {0}")]
+    /// The header is malformed
+    InvalidHeader(String),
+    #[error("unsupported document version {0}, expected at most {VERSION}")]
+    /// The document is from a newer version
+    UnsupportedVersion(u32),
+    #[error("line {0}: metadata must be of the form `@key=value`")]
+    /// A metadata line has no `=`
+    InvalidMetadata(usize),
+    #[error("line {0}: metadata is not followed by an entry")]
+    /// Metadata at the end of the document
+    DanglingMetadata(usize),
+    #[error("entry {index}: {source}")]
+    /// Error decoding a plain entry
+    Parse {
+        /// Index of the entry
+        index: usize,
+        /// The underlying error
+        source: parse::Error,
+    },
+    #[error("entry {index}: {source}")]
+    /// Error encoding or decoding a delta entry
+    Delta {
+        /// Index of the entry
+        index: usize,
+        /// The underlying error
+        source: delta::Error,
+    },
+    #[error(transparent)]
+    /// Error encoding an entry
+    Encode(#[from] encode::Error),
+}
+
+/// How entries are encoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Dialect {
+    /// Each entry is a brex string
+    #[default]
+    Plain,
+    /// Entries may be deltas against previous entries. See [`crate::delta`].
+    Delta,
+}
+
+/// How entries are separated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Delimiter {
+    /// One entry per line
+    #[default]
+    Line,
+    /// Entries are separated by blank lines, and may span multiple lines
+    Blank,
+}
+
+/// A document's header line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    /// The format version
+    pub version: u32,
+    /// How entries are encoded
+    pub dialect: Dialect,
+    /// How entries are separated
+    pub delimiter: Delimiter,
+}
+
+impl Default for Header {
+    fn default() -> Self {
+        Self {
+            version: VERSION,
+            dialect: Dialect::Plain,
+            delimiter: Delimiter::Line,
+        }
+    }
+}
+
+/// A single entry of a [`BrexDocument`]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Entry {
+    /// Comments before the entry, without their leading `#`
+    pub comments: Vec<String>,
+    /// Key/value metadata, in order
+    pub metadata: Vec<(String, String)>,
+    /// The encoded entry, in the document's [`Dialect`]
+    pub brex: String,
+}
+
+/// Each name given to [`BrexDocument::from_plaintext_checked()`], alongside the fallback its entry took
+pub type Fallbacks<'a> = Vec<(&'a str, Option<Fallback>)>;
+
+/// A parsed brex document. See the [module docs](self).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BrexDocument {
+    /// The document's header
+    pub header: Header,
+    /// The document's entries
+    pub entries: Vec<Entry>,
+    /// Comments after the last entry, without their leading `#`
+    pub comments: Vec<String>,
+}
+
+impl BrexDocument {
+    /// Create an empty document
+    pub fn new(header: Header) -> Self {
+        Self {
+            header,
+            entries: Vec::new(),
+            comments: Vec::new(),
+        }
+    }
+
+    /// Parse a document, without decoding its entries. To read a document line by line, see [`Reader`].
+    pub fn parse(input: &str) -> Result<Self, Error> {
+        let mut reader = Reader::new(Header::default());
+        let mut entries = Vec::new();
+        for line in input.lines() {
+            entries.extend(reader.read_line(line)?);
+        }
+        let header = reader.header().clone();
+        let (last, comments) = reader.finish()?;
+        entries.extend(last);
+        Ok(Self {
+            header,
+            entries,
+            comments,
+        })
+    }
+
+    /// Encode plaintext names into a document, one per line. Lines starting with `#` are kept as comments, and blank lines are skipped.
+    ///
+    /// Every entry is verified to decode back to its name, folded according to [`EncodeOptions::case`].
+    /// With the `rayon` feature, plain entries are encoded in parallel.
+    pub fn from_plaintext(
+        input: &str,
+        header: Header,
+        options: &EncodeOptions,
+    ) -> Result<Self, Error> {
+        Ok(Self::from_plaintext_checked(input, header, options)?.0)
+    }
+
+    /// [`Self::from_plaintext()`], also returning each entry's trimmed name, alongside the fallback it took (see [`Brex::encode_checked()`]).
+    ///
+    /// Delta entries never fall back, as they are verified by [`DeltaEncoder`] instead.
+    pub fn from_plaintext_checked<'a>(
+        input: &'a str,
+        header: Header,
+        options: &EncodeOptions,
+    ) -> Result<(Self, Fallbacks<'a>), Error> {
+        let mut document = Self::new(header);
+        let mut names = Vec::new();
+        for line in input.lines().filter(|line| !line.trim().is_empty()) {
+            match line.strip_prefix('#') {
+                Some(comment) => document.comments.push(comment.to_string()),
+                None => {
+                    names.push(line.trim());
+                    document.entries.push(Entry {
+                        comments: core::mem::take(&mut document.comments),
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+
+        let mut fallbacks = alloc::vec![None; names.len()];
+        match document.header.dialect {
+            Dialect::Plain => {
+                for ((entry, fallback), (brex, taken)) in document
+                    .entries
+                    .iter_mut()
+                    .zip(&mut fallbacks)
+                    .zip(encode_all(&names, options)?)
+                {
+                    entry.brex = brex;
+                    *fallback = taken;
+                }
+            }
+            Dialect::Delta => {
+                let mut encoder = DeltaEncoder::new(DeltaOptions {
+                    encode: options.clone(),
+                    ..Default::default()
+                });
+                for (index, (entry, name)) in document.entries.iter_mut().zip(&names).enumerate() {
+                    entry.brex = encoder
+                        .encode(name)
+                        .map_err(|source| Error::Delta { index, source })?;
+                }
+            }
+        }
+        Ok((document, names.into_iter().zip(fallbacks).collect()))
+    }
+
+    /// Decode every entry to plaintext
+    pub fn decode(&self) -> Result<Vec<String>, Error> {
+        let mut decoder = Decoder::new(self.header.dialect);
+        self.entries
+            .iter()
+            .map(|entry| decoder.decode(entry))
+            .collect()
+    }
+
+    /// Decode every entry, writing them one per line with their comments, as read by [`Self::from_plaintext()`]
+    pub fn to_plaintext(&self) -> Result<String, Error> {
+        let mut out = String::new();
+        for (entry, name) in self.entries.iter().zip(self.decode()?) {
+            for comment in &entry.comments {
+                out.push('#');
+                out.push_str(comment);
+                out.push('\n');
+            }
+            out.push_str(&name);
+            out.push('\n');
+        }
+        for comment in &self.comments {
+            out.push('#');
+            out.push_str(comment);
+            out.push('\n');
+        }
+        Ok(out)
+    }
+}
+
+/// Reads a document line by line, yielding each entry once it's complete, so the whole document never needs to be in memory.
+///
+/// ```
+/// # use brex::document::{Decoder, Header, Reader};
+/// let mut reader = Reader::new(Header::default());
+/// let mut entries = Vec::new();
+/// for line in ["#!brex version=1", "# skins", "a❮_skins{_skin{0→1}}❯", "b"] {
+///     entries.extend(reader.read_line(line).unwrap());
+/// }
+/// let (last, comments) = reader.finish().unwrap();
+/// entries.extend(last);
+/// assert!(comments.is_empty());
+///
+/// let mut decoder = Decoder::new(Header::default().dialect);
+/// assert_eq!(entries[0].comments, [" skins"]);
+/// assert_eq!(decoder.decode(&entries[0]).unwrap(), "a_skins_skin0_skins_skin1");
+/// assert_eq!(decoder.decode(&entries[1]).unwrap(), "b");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Reader {
+    header: Header,
+    /// The number of lines read
+    line: usize,
+    /// The entry being read
+    entry: Entry,
+    /// The line of the last metadata read
+    metadata_line: Option<usize>,
+}
+
+impl Reader {
+    /// Create a reader, using `header` unless the document starts with its own
+    pub fn new(header: Header) -> Self {
+        Self {
+            header,
+            line: 0,
+            entry: Entry::default(),
+            metadata_line: None,
+        }
+    }
+
+    /// The document's header. Only final once the first line has been read.
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Read the next line, without its line ending, returning the entry it completes, if any
+    pub fn read_line(&mut self, line: &str) -> Result<Option<Entry>, Error> {
+        self.line += 1;
+        if self.line == 1
+            && let Some(header) = line.strip_prefix(HEADER)
+        {
+            self.header = header.parse()?;
+            return Ok(None);
+        }
+
+        let in_entry = !self.entry.brex.is_empty();
+        if line.trim().is_empty() {
+            return Ok(in_entry.then(|| core::mem::take(&mut self.entry)));
+        }
+        if in_entry && self.header.delimiter == Delimiter::Blank {
+            self.entry.brex.push('\n');
+            self.entry.brex.push_str(line);
+            return Ok(None);
+        }
+        let complete = in_entry.then(|| core::mem::take(&mut self.entry));
+        if let Some(comment) = line.strip_prefix('#') {
+            self.entry.comments.push(comment.to_string());
+        } else if let Some(metadata) = line.strip_prefix('@') {
+            let (key, value) = metadata
+                .split_once('=')
+                .ok_or(Error::InvalidMetadata(self.line))?;
+            self.entry
+                .metadata
+                .push((key.to_string(), value.to_string()));
+            self.metadata_line = Some(self.line);
+        } else {
+            self.entry.brex = line.strip_prefix('\\').unwrap_or(line).to_string();
+        }
+        Ok(complete)
+    }
+
+    /// Finish reading, returning the last entry if it wasn't complete yet, and the comments after the last entry
+    pub fn finish(self) -> Result<(Option<Entry>, Vec<String>), Error> {
+        if !self.entry.brex.is_empty() {
+            Ok((Some(self.entry), Vec::new()))
+        } else if let Some(line) = self
+            .metadata_line
+            .filter(|_| !self.entry.metadata.is_empty())
+        {
+            Err(Error::DanglingMetadata(line))
+        } else {
+            Ok((None, self.entry.comments))
+        }
+    }
+}
+
+/// Decodes a document's entries, which must be given in order, as delta entries depend on the ones before them
+#[derive(Debug, Clone)]
+pub struct Decoder {
+    /// The index of the next entry
+    index: usize,
+    dialect: DialectDecoder,
+}
+
+#[derive(Debug, Clone)]
+enum DialectDecoder {
+    Plain(Parser),
+    Delta(DeltaDecoder),
+}
+
+impl Decoder {
+    /// Create a decoder for entries of the given [`Dialect`]
+    pub fn new(dialect: Dialect) -> Self {
+        Self {
+            index: 0,
+            dialect: match dialect {
+                Dialect::Plain => DialectDecoder::Plain(Parser::new()),
+                Dialect::Delta => DialectDecoder::Delta(DeltaDecoder::default()),
+            },
+        }
+    }
+
+    /// Decode the next entry to plaintext
+    pub fn decode(&mut self, entry: &Entry) -> Result<String, Error> {
+        let index = self.index;
+        self.index += 1;
+        match &mut self.dialect {
+            DialectDecoder::Plain(parser) => parser
+                .decode(&entry.brex)
+                .map_err(|source| Error::Parse { index, source }),
+            DialectDecoder::Delta(decoder) => decoder
+                .decode(&entry.brex)
+                .map_err(|source| Error::Delta { index, source }),
+        }
+    }
+}
+
+/// Encode & verify each name. With the `rayon` feature, names are encoded in parallel.
+fn encode_all(
+    names: &[&str],
+    options: &EncodeOptions,
+) -> Result<Vec<(String, Option<Fallback>)>, Error> {
+    #[cfg(feature = "rayon")]
+    use rayon::iter::{IntoParallelRefIterator as _, ParallelIterator as _};
+
+    #[cfg(feature = "rayon")]
+    let names = names.par_iter();
+    #[cfg(not(feature = "rayon"))]
+    let names = names.iter();
+    Ok(names
+        .map(|name| {
+            let name = options.case.fold(name);
+            let checked = Brex::encode_checked_with(&name, options)?;
+            Ok((checked.brex.to_string(), checked.fallback))
+        })
+        .collect::<Result<Vec<_>, encode::Error>>()?)
+}
+
+/// Escape an entry as it's written in a document, with a leading `\` if it would otherwise start with `#`, `@` or `\`
+pub fn escape(entry: &str) -> Cow<'_, str> {
+    match entry.starts_with(['#', '@', '\\']) {
+        true => Cow::Owned(format!("\\{entry}")),
+        false => Cow::Borrowed(entry),
+    }
+}
+
+impl core::str::FromStr for Header {
+    type Err = Error;
+
+    /// Parse the `key=value` pairs after [`HEADER`]
+    fn from_str(s: &str) -> Result<Self, Error> {
+        let mut header = Header::default();
+        for pair in s.split_whitespace() {
+            let invalid = || Error::InvalidHeader(pair.to_string());
+            let (key, value) = pair.split_once('=').ok_or_else(invalid)?;
+            match key {
+                "version" => {
+                    header.version = value.parse().map_err(|_| invalid())?;
+                    if header.version > VERSION {
+                        return Err(Error::UnsupportedVersion(header.version));
+                    }
+                }
+                "dialect" => {
+                    header.dialect = match value {
+                        "plain" => Dialect::Plain,
+                        "delta" => Dialect::Delta,
+                        _ => return Err(invalid()),
+                    }
+                }
+                "delimiter" => {
+                    header.delimiter = match value {
+                        "line" => Delimiter::Line,
+                        "blank" => Delimiter::Blank,
+                        _ => return Err(invalid()),
+                    }
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        Ok(header)
+    }
+}
+
+impl fmt::Display for Header {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let dialect = match self.dialect {
+            Dialect::Plain => "plain",
+            Dialect::Delta => "delta",
+        };
+        let delimiter = match self.delimiter {
+            Delimiter::Line => "line",
+            Delimiter::Blank => "blank",
+        };
+        write!(
+            f,
+            "{HEADER} version={} dialect={dialect} delimiter={delimiter}",
+            self.version
+        )
+    }
+}
+
+/// Writes the document, including its header
+impl fmt::Display for BrexDocument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.header)?;
+        let blank = self.header.delimiter == Delimiter::Blank;
+        for (i, entry) in self.entries.iter().enumerate() {
+            if blank && i > 0 {
+                f.write_char('\n')?;
+            }
+            for comment in &entry.comments {
+                writeln!(f, "#{comment}")?;
+            }
+            for (key, value) in &entry.metadata {
+                writeln!(f, "@{key}={value}")?;
+            }
+            let pretty = match (blank, self.header.dialect) {
+                (true, Dialect::Plain) => Brex::parse(&entry.brex)
+                    .ok()
+                    .map(|brex| format!("{brex:#}")),
+                _ => None,
+            };
+            writeln!(f, "{}", escape(pretty.as_deref().unwrap_or(&entry.brex)))?;
+        }
+        if blank && !self.comments.is_empty() {
+            f.write_char('\n')?;
+        }
+        for comment in &self.comments {
+            writeln!(f, "#{comment}")?;
+        }
+        Ok(())
+    }
+}
@@ -2,18 +2,164 @@
 //!
 //! See [`Brex::encode()`] and [`crate::encode()`]
 
-use std::collections::{BTreeSet, HashMap};
+use alloc::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+    string::String,
+    vec,
+    vec::Vec,
+};
 
 use itertools::Itertools;
 
-use crate::{Brex, Group, Numeric, Suffix, util::split_inclusive_start};
+use crate::{
+    Alphabetic, Brex, Group, Numeric, Suffix, Text,
+    collation::{self, Value},
+    parse::{self, Parser},
+    util::split_inclusive_start,
+};
 
 #[derive(thiserror::Error, Debug)]
 /// Error encoding a brex string
 pub enum Error {
     #[error("Error formatting encoded string - {0}")]
     /// Error formatting the encoded string
-    FmtError(#[from] std::fmt::Error),
+    FmtError(#[from] core::fmt::Error),
+    #[error("Input {0:?} cannot be represented as brex, even as plaintext")]
+    /// The input does not survive a roundtrip, even when encoded as plaintext
+    Unrepresentable(String),
+    #[error("Input {0:?} must be folded with `Case::fold()` before encoding with `Case::Lower`")]
+    /// [`Case::Lower`] was set, but the input was not folded to lower case first
+    Unfolded(String),
+}
+
+/// Which length of the encoded string to minimise
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Length {
+    /// Length in [`char`]s
+    #[default]
+    Chars,
+    /// Length in UTF-8 bytes. The brex alphabet is mostly multi-byte, so this favours plaintext more often.
+    Bytes,
+}
+
+impl Length {
+    /// Measure the given string
+    pub fn of(&self, s: &str) -> usize {
+        match self {
+            Length::Chars => s.chars().count(),
+            Length::Bytes => s.len(),
+        }
+    }
+
+    /// Measure the given bytes. [`Length::Chars`] counts every byte that doesn't continue a UTF-8 char.
+    pub fn of_bytes(&self, bytes: &[u8]) -> usize {
+        match self {
+            Length::Chars => bytes.iter().filter(|byte| **byte & 0xc0 != 0x80).count(),
+            Length::Bytes => bytes.len(),
+        }
+    }
+}
+
+/// How letter case is treated when grouping parts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Case {
+    /// Parts only group when their case matches exactly, and the input is kept as-is
+    #[default]
+    Sensitive,
+    /// Fold the input to lower case, so parts group regardless of case (`Skins_Skin1` & `skins_skin2`).
+    ///
+    /// The original casing is not kept, and everything decodes to lower case. League hashes paths lower-cased,
+    /// so the hashes of decoded names are unchanged.
+    Lower,
+}
+
+impl Case {
+    /// Apply this case mode to an input, borrowing it if it's unchanged.
+    ///
+    /// [`Brex::encode_with()`] borrows from its input, so with [`Case::Lower`] it must be given folded input.
+    /// The string wrappers such as [`crate::encode_with()`] fold for you.
+    pub fn fold<'a>(&self, input: &'a str) -> Cow<'a, str> {
+        match self {
+            Case::Lower if !input.is_lower() => Cow::Owned(input.to_lowercase()),
+            _ => Cow::Borrowed(input),
+        }
+    }
+
+    /// [`Case::fold()`] for byte strings. Only ASCII letters are folded, as the bytes may not be UTF-8.
+    pub fn fold_bytes<'a>(&self, input: &'a [u8]) -> Cow<'a, [u8]> {
+        match self {
+            Case::Lower if !input.is_lower() => Cow::Owned(input.to_ascii_lowercase()),
+            _ => Cow::Borrowed(input),
+        }
+    }
+}
+
+/// Options for tuning [`Brex::encode_with()`]
+#[derive(Debug, Clone)]
+pub struct EncodeOptions {
+    /// The minimum number of members a group needs to be factored out.
+    /// Smaller groups are left as preamble/postamble, so the brex block only covers the best stretch of groups between them.
+    pub min_group_size: usize,
+    /// Whether to collapse numeric suffixes into [`Numeric`] lists (`_skin{0→32}`)
+    pub numerics: bool,
+    /// Whether to collapse runs of consecutive letter suffixes into [`Alphabetic`] ranges (`_variant{a→f}`)
+    pub letters: bool,
+    /// The number of trailing `.`-separated segments to keep as postamble (e.g `1` for `.bin`)
+    pub postamble_segments: usize,
+    /// Which length to minimise when choosing between candidate encodings
+    pub optimise_for: Length,
+    /// Whether to fold the input to lower case before grouping
+    pub case: Case,
+}
+
+impl Default for EncodeOptions {
+    fn default() -> Self {
+        Self {
+            min_group_size: 2,
+            numerics: true,
+            letters: true,
+            postamble_segments: 1,
+            optimise_for: Length::Chars,
+            case: Case::Sensitive,
+        }
+    }
+}
+
+/// A fallback taken by [`Brex::encode_checked()`] when the regular encoding did not decode back to its input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fallback {
+    /// Encoded without collapsing numeric or letter suffixes
+    NoNumerics,
+    /// Encoded as plaintext, without any groups
+    Plaintext,
+}
+
+impl core::fmt::Display for Fallback {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Fallback::NoNumerics => "no numerics",
+            Fallback::Plaintext => "plaintext",
+        })
+    }
+}
+
+/// The result of [`Brex::encode_checked()`]
+#[derive(Debug)]
+pub struct Checked<'a, T: ?Sized = str> {
+    /// The verified brex
+    pub brex: Brex<'a, T>,
+    /// The fallback that was taken, if the regular encoding did not roundtrip
+    pub fallback: Option<Fallback>,
+}
+
+impl<T: ?Sized> Clone for Checked<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            brex: self.brex.clone(),
+            fallback: self.fallback,
+        }
+    }
 }
 
 impl<'a> Brex<'a> {
@@ -21,146 +167,346 @@ impl<'a> Brex<'a> {
     ///
     /// See [`crate::encode()`] for a convenience wrapper that encodes directly to a [`String`].
     pub fn encode(input: &'a str) -> Result<Self, Error> {
-        let (line, postamble) = input
-            .rsplit_once(".")
-            .map(|(l, r)| (l, Some(&input[input.len() - (r.len() + 1)..])))
-            .unwrap_or((input, None));
-        let mut parts = split_inclusive_start(line, '_');
-
-        let mut a = Vec::<(&str, usize)>::new();
-        let mut b = Vec::<(&str, usize)>::new();
-        let mut parity = false;
-
-        for part in parts.clone() {
-            let set = match parity {
-                false => &mut a,
-                true => &mut b,
-            };
-
-            match set.last_mut() {
-                Some(last) => {
-                    if last.0 == part {
-                        last.1 += 1
-                    } else {
-                        set.push((part, 1));
-                    }
-                }
-                None => {
-                    set.push((part, 1));
-                }
-            }
+        Self::encode_with(input, &EncodeOptions::default())
+    }
+
+    /// Encode a string as brex, verifying that it decodes back to the input.
+    ///
+    /// If it does not, a simpler encoding without numerics or letters is tried, and failing that, plaintext.
+    /// The fallback taken (if any) is reported in [`Checked::fallback`].
+    ///
+    /// See [`crate::encode_checked()`] for a convenience wrapper that encodes directly to a [`String`].
+    pub fn encode_checked(input: &'a str) -> Result<Checked<'a>, Error> {
+        Self::encode_checked_with(input, &EncodeOptions::default())
+    }
+
+    /// [`Brex::encode_checked()`], with the given [`EncodeOptions`]. See [`Brex::encode_with()`] for [`Case::Lower`].
+    pub fn encode_checked_with(
+        input: &'a str,
+        options: &EncodeOptions,
+    ) -> Result<Checked<'a>, Error> {
+        encode_checked(input, options)
+    }
 
-            parity = !parity;
+    /// Try to encode a string as brex with the given [`EncodeOptions`]. Not guaranteed to be the minimal possible representation.
+    ///
+    /// With [`Case::Lower`], the input must already be folded with [`Case::fold()`], or [`Error::Unfolded`] is returned.
+    ///
+    /// See [`crate::encode_with()`] for a convenience wrapper that encodes directly to a [`String`].
+    pub fn encode_with(input: &'a str, options: &EncodeOptions) -> Result<Self, Error> {
+        encode(input, options)
+    }
+}
+
+/// The input, lossily converted to a [`String`] for error messages
+fn lossy<T: Text + ?Sized>(input: &T) -> String {
+    String::from_utf8_lossy(input.as_bytes()).into_owned()
+}
+
+/// [`Brex::encode_checked_with()`], over any [`Text`]
+pub(crate) fn encode_checked<'a, T: Text + ?Sized>(
+    input: &'a T,
+    options: &EncodeOptions,
+) -> Result<Checked<'a, T>, Error> {
+    if options.case == Case::Lower && !input.is_lower() {
+        return Err(Error::Unfolded(lossy(input)));
+    }
+    let mut parser = Parser::new();
+    let mut verified = |brex: &Brex<T>| {
+        parser
+            .decode_bytes(&brex.to_text())
+            .is_ok_and(|s| s == input.as_bytes())
+    };
+
+    if let Ok(brex) = encode(input, options)
+        && verified(&brex)
+    {
+        return Ok(Checked {
+            brex,
+            fallback: None,
+        });
+    }
+    let no_numerics = EncodeOptions {
+        numerics: false,
+        letters: false,
+        ..options.clone()
+    };
+    if (options.numerics || options.letters)
+        && let Ok(brex) = encode(input, &no_numerics)
+        && verified(&brex)
+    {
+        return Ok(Checked {
+            brex,
+            fallback: Some(Fallback::NoNumerics),
+        });
+    }
+    let brex = Brex::empty(input);
+    match verified(&brex) {
+        true => Ok(Checked {
+            brex,
+            fallback: Some(Fallback::Plaintext),
+        }),
+        false => Err(Error::Unrepresentable(lossy(input))),
+    }
+}
+
+/// [`Brex::encode_with()`], over any [`Text`]
+pub(crate) fn encode<'a, T: Text + ?Sized>(
+    input: &'a T,
+    options: &EncodeOptions,
+) -> Result<Brex<'a, T>, Error> {
+    if options.case == Case::Lower && !input.is_lower() {
+        return Err(Error::Unfolded(lossy(input)));
+    }
+    let bytes = input.as_bytes();
+    let mut line_end = bytes.len();
+    for _ in 0..options.postamble_segments {
+        match bytes[..line_end].iter().rposition(|byte| *byte == b'.') {
+            Some(dot) => line_end = dot,
+            None => break,
         }
-        //eprintln!("a: {a:?}");
-        //eprintln!("b: {b:?}");
-
-        // pick set with fewest prefixes
-        let (offset, best_set) = match a.len() < b.len() {
-            true => (0, a),
-            false => (1, b),
-        };
-
-        //eprintln!("\nbest set: {best_set:?}");
-
-        let Some(best_set_skip) = best_set.iter().position(|(_, count)| *count > 1) else {
-            // if there's no duplicates in the best set, there's no point encoding any groups
-            return Ok(Brex::empty(input));
-        };
-        let preamble = offset + (2 * best_set_skip);
-        let preamble = (&mut parts)
-            .take(preamble)
-            .map(|part| part.len())
-            .sum::<usize>();
-        let preamble = &line[..preamble];
-
-        //eprintln!("preamble: {preamble:?}");
-
-        let groups = best_set
+    }
+    // `.` is ascii, so always a char boundary
+    let line = input.slice(0..line_end).unwrap_or(input);
+    let parts = split_inclusive_start(line, '_').collect_vec();
+    Ok(encode_parts(input, &parts, options))
+}
+
+/// Encode, grouping on the given parts, which concatenate to the start of `input` (the rest is postamble).
+///
+/// Tries grouping on both odd & even parts, keeping whichever is shortest, or plaintext if neither is shorter.
+pub(crate) fn encode_parts<'a, T: Text + ?Sized>(
+    input: &'a T,
+    parts: &[&'a T],
+    options: &EncodeOptions,
+) -> Brex<'a, T> {
+    let mut best = (
+        options.optimise_for.of_bytes(input.as_bytes()),
+        Brex::empty(input),
+    );
+    for brex in [1, 0]
+        .into_iter()
+        .flat_map(|offset| encode_offset(input, parts, offset, options))
+    {
+        let len = options.optimise_for.of_bytes(&brex.to_text());
+        if len < best.0 {
+            best = (len, brex);
+        }
+    }
+    best.1
+}
+
+/// Encode, using the parts starting at `offset` as group prefixes.
+///
+/// Every group needs [`EncodeOptions::min_group_size`] members, so a smaller run splits the groups in two,
+/// and a brex is returned for each stretch of groups between smaller runs. None are returned if no group has enough members.
+fn encode_offset<'a, T: Text + ?Sized>(
+    input: &'a T,
+    parts: &[&'a T],
+    offset: usize,
+    options: &EncodeOptions,
+) -> Vec<Brex<'a, T>> {
+    // runs of consecutive (prefix, suffix) pairs sharing a prefix, alongside the part index they start at
+    let mut runs = Vec::<(usize, &'a T, Vec<&'a T>)>::new();
+    for (i, pair) in parts
+        .get(offset..)
+        .unwrap_or_default()
+        .chunks_exact(2)
+        .enumerate()
+    {
+        let (prefix, suffix) = (pair[0], pair[1]);
+        match runs.last_mut() {
+            Some((_, last, suffixes)) if *last == prefix => suffixes.push(suffix),
+            _ => runs.push((offset + 2 * i, prefix, vec![suffix])),
+        }
+    }
+
+    // the parser skips whitespace around prefixes & suffixes, so text with it at either end can't be grouped
+    let trimmed = |(_, prefix, suffixes): &(usize, &T, Vec<&T>)| {
+        parse::is_trimmed(prefix.as_bytes())
+            && suffixes
+                .iter()
+                .all(|suffix| parse::is_trimmed(suffix.as_bytes()))
+    };
+    let min_group_size = options.min_group_size.max(1);
+    runs.split(|run| run.2.len() < min_group_size || !trimmed(run))
+        .filter(|runs| !runs.is_empty())
+        .filter_map(|runs| encode_runs(input, parts, runs, options))
+        .collect()
+}
+
+/// Encode a stretch of runs as the groups of a brex, with the parts around them as preamble & postamble.
+///
+/// Returns [`None`] if the runs are out of collation order.
+fn encode_runs<'a, T: Text + ?Sized>(
+    input: &'a T,
+    parts: &[&'a T],
+    runs: &[(usize, &'a T, Vec<&'a T>)],
+    options: &EncodeOptions,
+) -> Option<Brex<'a, T>> {
+    // groups expand in collation order, so any other order can't round-trip
+    if !runs
+        .windows(2)
+        .all(|pair| collation::cmp(pair[0].1.as_bytes(), pair[1].1.as_bytes()).is_lt())
+    {
+        return None;
+    }
+
+    let offset_of = |part: usize| {
+        parts[..part]
             .iter()
-            .skip(best_set_skip)
-            .map(|(prefix, size)| {
-                let mut group = Vec::with_capacity(*size);
-                for _ in 0..*size {
-                    assert_eq!(parts.next(), Some(*prefix));
-                    group.push(parts.next().expect("enough parts"));
-                }
-                (prefix, group)
-            })
-            .collect::<Vec<_>>();
+            .map(|part| part.as_bytes().len())
+            .sum::<usize>()
+    };
+    let len = input.as_bytes().len();
+    let preamble = input.slice(0..offset_of(runs[0].0))?;
+    let (end, _, suffixes) = &runs[runs.len() - 1];
+    let postamble = input.slice(offset_of(end + 2 * suffixes.len())..len)?;
+    let text = |text: &'a T| Some(text).filter(|text| !text.as_bytes().is_empty());
 
-        let groups = groups
-            .into_iter()
-            .map(|(prefix, suffixes)| {
-                //eprintln!("\n{prefix}:\n  -     raw: {suffixes:?}");
-                let suffixes = suffixes
-                    .iter()
-                    .map(
-                        |suffix| match suffix.chars().position(|ch| ch.is_numeric()) {
-                            Some(first_numeric) => {
-                                let (left, right) = suffix.split_at(first_numeric);
-                                let right = right.parse::<u32>().ok();
-                                (left, right)
-                            }
-                            None => (*suffix, None),
-                        },
-                    )
-                    .collect::<Vec<_>>();
-
-                let mut non_numeric = Vec::new();
-                let mut numerics: HashMap<&str, BTreeSet<u32>> =
-                    HashMap::with_capacity(suffixes.len());
-
-                for (suffix, number) in suffixes {
-                    match number {
-                        Some(number) => {
-                            // we are assuming all the entries are sorted lexicographically
-                            numerics.entry(suffix).or_default().insert(number);
-                        }
-                        None => non_numeric.push(suffix),
-                    }
+    Some(Brex {
+        preamble: text(preamble),
+        groups: runs
+            .iter()
+            .map(|(_, prefix, suffixes)| encode_group(*prefix, suffixes, options))
+            .collect(),
+        postamble: text(postamble),
+    })
+}
+
+fn encode_group<'a, T: Text + ?Sized>(
+    prefix: &'a T,
+    suffixes: &[&'a T],
+    options: &EncodeOptions,
+) -> Group<'a, T> {
+    let mut non_numeric = Vec::new();
+    let mut numerics: BTreeMap<&T, BTreeSet<u32>> = BTreeMap::new();
+
+    for suffix in suffixes {
+        // the parser trims whitespace before a numeric block
+        let numeric = split_number(*suffix)
+            .filter(|(stem, _)| options.numerics && parse::is_trimmed(stem.as_bytes()));
+        match numeric {
+            Some((suffix, number)) => {
+                // we are assuming all the entries are in `collation::Value` order
+                numerics.entry(suffix).or_default().insert(number);
+            }
+            None => non_numeric.push(*suffix),
+        }
+    }
+
+    let numerics = numerics
+        .into_iter()
+        .map(|(k, v)| {
+            let mut ranges = Vec::<Numeric>::new();
+            for v in v {
+                match ranges.last_mut() {
+                    Some(last) if last.end() + 1 == v => *last = last.with_end(v).unwrap(),
+                    _ => ranges.push(Numeric::new(v)),
                 }
+            }
+            (k, ranges)
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    let non_numeric = match options.letters {
+        true => encode_letters(&non_numeric),
+        false => non_numeric.into_iter().map(Suffix::simple).collect(),
+    };
 
-                let numerics = numerics
+    Group {
+        prefix,
+        suffixes: non_numeric
+            .into_iter()
+            .chain(
+                numerics
                     .into_iter()
-                    .map(|(k, v)| {
-                        let mut ranges = Vec::<Numeric>::new();
-                        for v in v {
-                            match ranges.last_mut() {
-                                Some(last) if last.end() + 1 == v => {
-                                    *last = last.with_end(v).unwrap()
-                                }
-                                _ => ranges.push(Numeric::new(v)),
-                            }
-                        }
-                        (k, ranges)
-                    })
-                    .collect::<HashMap<_, _>>();
-
-                //eprintln!("  -  normal: {non_numeric:?}");
-                //eprintln!("  - numeric: {numerics:?}");
-                Group {
-                    prefix,
-                    suffixes: non_numeric
-                        .into_iter()
-                        .map(Suffix::simple)
-                        .chain(
-                            numerics
-                                .into_iter()
-                                .map(|(suffix, numerics)| Suffix::numeric(suffix, numerics)),
-                        )
-                        .collect(),
-                }
-            })
-            .collect_vec();
-
-        Ok(Brex {
-            preamble: match preamble.is_empty() {
-                true => None,
-                false => Some(preamble),
-            },
-            groups,
-            postamble,
+                    .map(|(suffix, numerics)| Suffix::numeric(suffix, numerics)),
+            )
+            .collect(),
+    }
+}
+
+/// Split a suffix into the text before its first digit & the number from there to its end.
+///
+/// Returns [`None`] if the rest isn't all digits, or has leading zeros, which wouldn't survive expansion.
+fn split_number<T: Text + ?Sized>(suffix: &T) -> Option<(&T, u32)> {
+    let bytes = suffix.as_bytes();
+    let first = bytes.iter().position(u8::is_ascii_digit)?;
+    let digits = &bytes[first..];
+    if !digits.iter().all(u8::is_ascii_digit) || (digits[0] == b'0' && digits.len() > 1) {
+        return None;
+    }
+    let number = digits.iter().try_fold(0u32, |value, digit| {
+        value.checked_mul(10)?.checked_add(u32::from(digit - b'0'))
+    })?;
+    Some((suffix.slice(0..first)?, number))
+}
+
+/// Collapse runs of plain suffixes that only differ by consecutive trailing letters (`_a`, `_b`, `_c`) into [`Alphabetic`] ranges.
+///
+/// Runs must already be in string order, as that's the order they expand in, so every member of a run has the same number of letters.
+fn encode_letters<'a, T: Text + ?Sized>(suffixes: &[&'a T]) -> Vec<Suffix<'a, T>> {
+    let mut out = Vec::with_capacity(suffixes.len());
+    let mut i = 0;
+    while i < suffixes.len() {
+        // the longest run, trying each number of trailing letters
+        let run = (1..=MAX_LETTERS)
+            .filter_map(|letters| letter_run(&suffixes[i..], letters))
+            .fold(None, |best: Option<(&T, u32, usize)>, run| match best {
+                Some(best) if best.2 >= run.2 => Some(best),
+                _ => Some(run),
+            });
+        match run {
+            Some((stem, start, len)) if len >= MIN_LETTER_RUN => {
+                let end = start + (len - 1) as u32;
+                out.push(Suffix::alphabetic(
+                    stem,
+                    vec![Alphabetic::Range(start, end)],
+                ));
+                i += len;
+            }
+            _ => {
+                out.push(Suffix::simple(suffixes[i]));
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// The fewest consecutive letter suffixes worth collapsing into a range (`_{a→c}` vs `_a,_b,_c`)
+const MIN_LETTER_RUN: usize = 3;
+/// The most trailing letters considered a sequence
+const MAX_LETTERS: usize = 3;
+
+/// The stem, starting index & length of the run of suffixes at the start of `suffixes` that end in consecutive sequences of `letters` letters
+fn letter_run<'a, T: Text + ?Sized>(
+    suffixes: &[&'a T],
+    letters: usize,
+) -> Option<(&'a T, u32, usize)> {
+    let (stem, start) = split_letters(suffixes[0], letters)?;
+    let len = 1 + suffixes
+        .windows(2)
+        .zip(1..)
+        .take_while(|(pair, offset)| {
+            let (prev, next) = (start + offset - 1, start + offset);
+            Value::Letters(prev) < Value::Letters(next)
+                && pair[1] > pair[0]
+                && split_letters(pair[1], letters) == Some((stem, next))
         })
+        .count();
+    Some((stem, start, len))
+}
+
+/// Split a suffix into its stem & the index of its last `letters` letters
+fn split_letters<T: Text + ?Sized>(suffix: &T, letters: usize) -> Option<(&T, u32)> {
+    let bytes = suffix.as_bytes();
+    let split = bytes.len().checked_sub(letters)?;
+    let stem = suffix.slice(0..split)?;
+    // the parser trims whitespace before a numeric block
+    if split == 0 || parse::trim_end(bytes, 0..split).end != split {
+        return None;
     }
+    Some((stem, Alphabetic::index(&bytes[split..])?))
 }
@@ -1,14 +1,36 @@
 //! Crate for encoding & decoding brex strings, an encoding dedicated to representing repetitive League of Legends game file names succinctly.
+//!
+//! The `std` feature is enabled by default. Without it, the crate is `no_std` and only requires `alloc`.
+//! The `rayon` feature enables the `batch` module, for encoding many lines in parallel.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![deny(missing_docs)]
 #![deny(warnings)]
 
+extern crate alloc;
+
+use alloc::string::{String, ToString as _};
+
 mod decode;
 mod models;
 mod util;
 
 pub mod alphabet;
+#[cfg(any(feature = "arbitrary", test))]
+pub mod arbitrary;
+#[cfg(any(feature = "rayon", test))]
+pub mod batch;
+pub mod binary;
+pub mod builder;
+pub mod bytes;
+pub mod collation;
+pub mod delta;
+pub mod diff;
+pub mod document;
 pub mod encode;
 pub mod parse;
+pub mod paths;
+pub mod pattern;
+pub mod stats;
 
 pub use models::*;
 
@@ -22,9 +44,35 @@ pub fn encode(input: &str) -> Result<String, encode::Error> {
     Ok(Brex::encode(input)?.to_string())
 }
 
+/// Encode text to a brex string with the given [`encode::EncodeOptions`].
+///
+/// This is a convenience wrapper around [`Brex::encode_with`], stringifying the resulting [`Brex`].
+/// The input is folded according to [`encode::EncodeOptions::case`] first.
+pub fn encode_with(input: &str, options: &encode::EncodeOptions) -> Result<String, encode::Error> {
+    Ok(Brex::encode_with(&options.case.fold(input), options)?.to_string())
+}
+
+/// Encode text to a brex string, verifying that it decodes back to the input.
+///
+/// This is a convenience wrapper around [`Brex::encode_checked`], stringifying the resulting [`Brex`]
+pub fn encode_checked(input: &str) -> Result<(String, Option<encode::Fallback>), encode::Error> {
+    encode_checked_with(input, &encode::EncodeOptions::default())
+}
+
+/// [`encode_checked()`], with the given [`encode::EncodeOptions`].
+/// The input is folded according to [`encode::EncodeOptions::case`] first.
+pub fn encode_checked_with(
+    input: &str,
+    options: &encode::EncodeOptions,
+) -> Result<(String, Option<encode::Fallback>), encode::Error> {
+    let input = options.case.fold(input);
+    let checked = Brex::encode_checked_with(&input, options)?;
+    Ok((checked.brex.to_string(), checked.fallback))
+}
+
 /// Parse and expand a brex string.
 ///
-/// This is a convenience wrapper around [`Brex::parse()`] and [`Brex::expand()`]
+/// This expands directly from the parsed string, without building a [`Brex`]. To decode many strings, see [`parse::Parser`].
 pub fn decode(encoded: &str) -> Result<String, parse::Error> {
-    Ok(Brex::parse(encoded)?.expand())
+    parse::Parser::new().decode(encoded)
 }
@@ -0,0 +1,83 @@
+use core::fmt;
+
+use crate::{alphabet::NUMERIC_RANGE_DELIM, util::letters};
+
+/// A letter sequence/range of letter sequences, such as `a`, `a→f` or `aa→ad`
+///
+/// Sequences are stored as their index in the order `a`, `b`, …, `z`, `aa`, `ab`, …, so `a` is `0`, `z` is `25` and `aa` is `26`.
+/// Only lowercase ASCII letters are supported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alphabetic {
+    /// A single sequence
+    Single(u32),
+    /// A range of sequences (inclusive)
+    Range(u32, u32),
+}
+
+impl Alphabetic {
+    /// Create a single sequence
+    pub fn new(v: u32) -> Self {
+        Self::Single(v)
+    }
+
+    /// The index of a lowercase letter sequence (as a [`str`] or bytes), or [`None`] if it is empty, has other characters, or is too long for a [`u32`]
+    ///
+    /// ```
+    /// # use brex::Alphabetic;
+    /// assert_eq!(Alphabetic::index("a"), Some(0));
+    /// assert_eq!(Alphabetic::index("aa"), Some(26));
+    /// assert_eq!(Alphabetic::index("A"), None);
+    /// ```
+    pub fn index(letters: impl AsRef<[u8]>) -> Option<u32> {
+        let letters = letters.as_ref();
+        if letters.is_empty() {
+            return None;
+        }
+        let value = letters.iter().try_fold(0u64, |value, &letter| {
+            let letter = match letter {
+                b'a'..=b'z' => u64::from(letter - b'a' + 1),
+                _ => return None,
+            };
+            Some(value * 26 + letter).filter(|value| *value <= u64::from(u32::MAX) + 1)
+        })?;
+        u32::try_from(value - 1).ok()
+    }
+
+    /// The start of this sequence. [`Alphabetic::Single`]'s return their same single value for [`Self::start()`] and [`Self::end()`]
+    pub fn start(&self) -> u32 {
+        match self {
+            Self::Single(start) | Self::Range(start, _) => *start,
+        }
+    }
+
+    /// The end of this sequence. [`Alphabetic::Single`]'s return their same single value for [`Self::start()`] and [`Self::end()`]
+    pub fn end(&self) -> u32 {
+        match self {
+            Self::Single(end) | Self::Range(_, end) => *end,
+        }
+    }
+
+    /// Creates a new sequence with the given end. Converts [`Alphabetic::Single`]'s to [`Alphabetic::Range`]'s
+    pub fn with_end(self, end: u32) -> Option<Self> {
+        match self {
+            Self::Single(start) | Self::Range(start, _) if end >= start => {
+                Some(Self::Range(start, end))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Alphabetic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Single(v) => f.write_str(letters(*v, &mut [0; 7])),
+            Self::Range(start, end) => write!(
+                f,
+                "{}{NUMERIC_RANGE_DELIM}{}",
+                letters(*start, &mut [0; 7]),
+                letters(*end, &mut [0; 7])
+            ),
+        }
+    }
+}
@@ -1,27 +1,31 @@
-use core::fmt;
-use std::fmt::Write as _;
+use alloc::{vec, vec::Vec};
+use core::fmt::{self, Write as _};
 
 use crate::{
-    Group, Suffix,
-    alphabet::{BREX_BLOCK, GROUP_BLOCK, GROUP_SUFFIX_SEP, NUMERIC_BLOCK, NUMERIC_LIST_SEP},
+    Group, Text,
+    alphabet::{BREX_BLOCK, GROUP_BLOCK, GROUP_SUFFIX_SEP},
+    models::write_block,
+    util::ByteWriter,
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, PartialEq, Eq)]
 /// IR of a brex string.
 ///
 /// Created via [`Brex::encode()`] or [`Brex::parse()`].
 /// To expand out to plaintext, see [`Brex::expand()`].
-pub struct Brex<'a> {
+///
+/// Borrows from [`str`] by default. Brex over `[u8]`, for names that aren't valid UTF-8, are created via [`crate::bytes`].
+pub struct Brex<'a, T: ?Sized = str> {
     /// Plaintext before the `<`
-    pub preamble: Option<&'a str>,
+    pub preamble: Option<&'a T>,
     /// The groups within the `<>` pair
-    pub groups: Vec<Group<'a>>,
+    pub groups: Vec<Group<'a, T>>,
     /// Plaintext after the `>`
-    pub postamble: Option<&'a str>,
+    pub postamble: Option<&'a T>,
 }
 
-impl<'a> Brex<'a> {
-    pub(crate) fn empty(preamble: &'a str) -> Self {
+impl<'a, T: ?Sized> Brex<'a, T> {
+    pub(crate) fn empty(preamble: &'a T) -> Self {
         Self {
             preamble: Some(preamble),
             groups: vec![],
@@ -30,6 +34,67 @@ impl<'a> Brex<'a> {
     }
 }
 
+impl<T: ?Sized> Clone for Brex<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            preamble: self.preamble,
+            groups: self.groups.clone(),
+            postamble: self.postamble,
+        }
+    }
+}
+
+impl<T: Text + ?Sized> Brex<'_, T> {
+    /// Write the compact brex string as bytes, appending to `out`. Matches [`Brex`]'s `Display` for [`str`] brex.
+    pub fn write_text(&self, out: &mut Vec<u8>) {
+        if let Some(preamble) = self.preamble {
+            out.extend_from_slice(preamble.as_bytes());
+        }
+        if self.groups.is_empty() {
+            return;
+        }
+        let mut out = ByteWriter(out);
+        // writing to a `Vec` is infallible
+        let _ = out.write_char(BREX_BLOCK.start);
+        for Group { prefix, suffixes } in &self.groups {
+            out.0.extend_from_slice(prefix.as_bytes());
+            let _ = out.write_char(GROUP_BLOCK.start);
+            for (i, suffix) in suffixes.iter().enumerate() {
+                if i > 0 {
+                    let _ = out.write_char(GROUP_SUFFIX_SEP);
+                }
+                out.0.extend_from_slice(suffix.suffix.as_bytes());
+                let _ = write_block(
+                    &mut out,
+                    suffix.numerics.as_deref(),
+                    suffix.letters.as_deref(),
+                );
+            }
+            let _ = out.write_char(GROUP_BLOCK.end);
+        }
+        let _ = out.write_char(BREX_BLOCK.end);
+        if let Some(postamble) = self.postamble {
+            out.0.extend_from_slice(postamble.as_bytes());
+        }
+    }
+
+    /// The compact brex string as bytes. See [`Self::write_text()`].
+    pub fn to_text(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.write_text(&mut out);
+        out
+    }
+}
+
+/// Displays the compact, single-line brex string.
+///
+/// The alternate form (`{:#}`) pretty prints each group on its own indented line, with suffixes aligned:
+/// ```text
+/// aatrox❮
+///     _base {_a, _b}
+///     _skins{_root, _skin{0→2}}
+/// ❯.bin
+/// ```
 impl fmt::Display for Brex<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let Self {
@@ -45,28 +110,38 @@ impl fmt::Display for Brex<'_> {
             return Ok(());
         }
 
+        let pretty = f.alternate();
+        let width = match pretty {
+            true => groups
+                .iter()
+                .map(|group| group.prefix.chars().count())
+                .max()
+                .unwrap_or_default(),
+            false => 0,
+        };
+
         f.write_char(BREX_BLOCK.start)?;
         for Group { prefix, suffixes } in groups {
-            f.write_str(prefix)?;
+            if pretty {
+                f.write_str("\n    ")?;
+            }
+            write!(f, "{prefix:width$}")?;
             f.write_char(GROUP_BLOCK.start)?;
-            for (i, Suffix { suffix, numerics }) in suffixes.iter().enumerate() {
-                f.write_str(suffix)?;
-                if let Some(numerics) = numerics {
-                    f.write_char(NUMERIC_BLOCK.start)?;
-                    for (i, numeric) in numerics.iter().enumerate() {
-                        numeric.fmt(f)?;
-                        if i < numerics.len() - 1 {
-                            f.write_char(NUMERIC_LIST_SEP)?;
-                        }
-                    }
-                    f.write_char(NUMERIC_BLOCK.end)?;
-                }
+            for (i, suffix) in suffixes.iter().enumerate() {
+                f.write_str(suffix.suffix)?;
+                write_block(f, suffix.numerics.as_deref(), suffix.letters.as_deref())?;
                 if i < suffixes.len() - 1 {
                     f.write_char(GROUP_SUFFIX_SEP)?;
+                    if pretty {
+                        f.write_char(' ')?;
+                    }
                 }
             }
             f.write_char(GROUP_BLOCK.end)?;
         }
+        if pretty {
+            f.write_char('\n')?;
+        }
         f.write_char(BREX_BLOCK.end)?;
         if let Some(postamble) = postamble {
             f.write_str(postamble)?;
@@ -1,10 +1,21 @@
+use alloc::vec::Vec;
+
 use crate::Suffix;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, PartialEq, Eq)]
 /// A group of one prefix & multiple suffixes. Each suffix (and each numeric of each suffix) is appended to the prefix, to get the final expanded result.
-pub struct Group<'a> {
+pub struct Group<'a, T: ?Sized = str> {
     /// The group's prefix. Present before each suffix.
-    pub prefix: &'a str,
+    pub prefix: &'a T,
     /// The group's suffixes. Preceded by [`Self::prefix`]
-    pub suffixes: Vec<Suffix<'a>>,
+    pub suffixes: Vec<Suffix<'a, T>>,
+}
+
+impl<T: ?Sized> Clone for Group<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            prefix: self.prefix,
+            suffixes: self.suffixes.clone(),
+        }
+    }
 }
@@ -1,3 +1,6 @@
+mod alphabetic;
+pub use alphabetic::*;
+
 mod brex;
 pub use brex::*;
 
@@ -8,4 +11,8 @@ mod numeric;
 pub use numeric::*;
 
 mod suffix;
+pub(crate) use suffix::write_block;
 pub use suffix::*;
+
+mod text;
+pub use text::*;
@@ -3,7 +3,7 @@ use core::fmt;
 use crate::alphabet::NUMERIC_RANGE_DELIM;
 
 /// A number/range of numbers
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Numeric {
     /// A single number
     Single(u32),
@@ -52,9 +52,9 @@ impl fmt::Display for Numeric {
 }
 
 impl TryFrom<(&str, Option<&str>)> for Numeric {
-    type Error = std::num::ParseIntError;
+    type Error = core::num::ParseIntError;
 
-    fn try_from(value: (&str, Option<&str>)) -> std::result::Result<Self, Self::Error> {
+    fn try_from(value: (&str, Option<&str>)) -> core::result::Result<Self, Self::Error> {
         match value {
             (start, Some(end)) => Ok(Self::Range(start.parse()?, end.parse()?)),
             (start, None) => Ok(Self::Single(start.parse()?)),
@@ -1,27 +1,87 @@
-use crate::Numeric;
+use alloc::vec::Vec;
+use core::fmt;
 
-#[derive(Debug, Clone)]
+use crate::{
+    Alphabetic, Numeric,
+    alphabet::{NUMERIC_BLOCK, NUMERIC_LIST_SEP},
+};
+
+#[derive(Debug, PartialEq, Eq)]
 /// The deduplicated suffix in a [`super::Group`]
-pub struct Suffix<'a> {
+pub struct Suffix<'a, T: ?Sized = str> {
     /// The suffix in question
-    pub suffix: &'a str,
+    pub suffix: &'a T,
     /// [`Numeric`] suffixes of this suffix
     pub numerics: Option<Vec<Numeric>>,
+    /// [`Alphabetic`] suffixes of this suffix. Written in the same block as any numerics, after them.
+    pub letters: Option<Vec<Alphabetic>>,
 }
 
-impl<'a> Suffix<'a> {
+impl<'a, T: ?Sized> Suffix<'a, T> {
     /// Create a plain suffix, without any numerics
-    pub fn simple(suffix: &'a str) -> Self {
+    pub fn simple(suffix: &'a T) -> Self {
         Self {
             suffix,
             numerics: None,
+            letters: None,
         }
     }
     /// Creates a suffix with numerics
-    pub fn numeric(suffix: &'a str, numerics: Vec<Numeric>) -> Self {
+    pub fn numeric(suffix: &'a T, numerics: Vec<Numeric>) -> Self {
         Self {
             suffix,
             numerics: Some(numerics),
+            letters: None,
+        }
+    }
+    /// Creates a suffix with letter sequences
+    pub fn alphabetic(suffix: &'a T, letters: Vec<Alphabetic>) -> Self {
+        Self {
+            suffix,
+            numerics: None,
+            letters: Some(letters),
+        }
+    }
+
+    /// Whether this suffix has a numeric block, of numerics and/or letters
+    pub fn has_block(&self) -> bool {
+        self.numerics.is_some() || self.letters.is_some()
+    }
+}
+
+impl<T: ?Sized> Clone for Suffix<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            suffix: self.suffix,
+            numerics: self.numerics.clone(),
+            letters: self.letters.clone(),
+        }
+    }
+}
+
+/// Write a numeric block of `numerics` followed by `letters`, if either is present
+pub(crate) fn write_block(
+    f: &mut impl fmt::Write,
+    numerics: Option<&[Numeric]>,
+    letters: Option<&[Alphabetic]>,
+) -> fmt::Result {
+    if numerics.is_none() && letters.is_none() {
+        return Ok(());
+    }
+    f.write_char(NUMERIC_BLOCK.start)?;
+    let numerics = numerics
+        .into_iter()
+        .flatten()
+        .map(|n| n as &dyn fmt::Display);
+    let letters = letters
+        .into_iter()
+        .flatten()
+        .map(|l| l as &dyn fmt::Display);
+    for (i, value) in numerics.chain(letters).enumerate() {
+        if i > 0 {
+            f.write_char(NUMERIC_LIST_SEP)?;
         }
+        write!(f, "{value}")?;
     }
+    f.write_char(NUMERIC_BLOCK.end)
 }
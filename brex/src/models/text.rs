@@ -0,0 +1,60 @@
+/// Text that brex can be built over: [`str`], or `[u8]` for names that aren't valid UTF-8. See [`crate::bytes`].
+///
+/// The brex alphabet is always UTF-8 encoded, and `[u8]` text is only ever split on ASCII bytes or at the alphabet's characters.
+pub trait Text: Ord {
+    /// The text as bytes
+    fn as_bytes(&self) -> &[u8];
+    /// Slice by byte range, or [`None`] if it is out of bounds or not on a char boundary
+    fn slice(&self, range: core::ops::Range<usize>) -> Option<&Self>;
+    /// The empty text
+    fn empty<'a>() -> &'a Self;
+    /// The byte length of the first char. Every byte is its own char in `[u8]`.
+    fn first_len(&self) -> usize;
+    /// Whether the text is already folded to lower case. `[u8]` text only folds ASCII letters.
+    fn is_lower(&self) -> bool;
+}
+
+impl Text for str {
+    fn as_bytes(&self) -> &[u8] {
+        str::as_bytes(self)
+    }
+
+    fn slice(&self, range: core::ops::Range<usize>) -> Option<&Self> {
+        self.get(range)
+    }
+
+    fn empty<'a>() -> &'a Self {
+        ""
+    }
+
+    fn first_len(&self) -> usize {
+        self.chars().next().map_or(0, char::len_utf8)
+    }
+
+    fn is_lower(&self) -> bool {
+        self.chars()
+            .all(|ch| ch.to_lowercase().eq(core::iter::once(ch)))
+    }
+}
+
+impl Text for [u8] {
+    fn as_bytes(&self) -> &[u8] {
+        self
+    }
+
+    fn slice(&self, range: core::ops::Range<usize>) -> Option<&Self> {
+        self.get(range)
+    }
+
+    fn empty<'a>() -> &'a Self {
+        &[]
+    }
+
+    fn first_len(&self) -> usize {
+        usize::from(!self.is_empty())
+    }
+
+    fn is_lower(&self) -> bool {
+        !self.iter().any(u8::is_ascii_uppercase)
+    }
+}
@@ -1,106 +1,593 @@
 //! Parsing brex strings
 //!
-//! See [`Brex::parse()`]
-
-use nom::{
-    Finish, Parser as _,
-    bytes::complete::{is_not, take_till, take_while1},
-    character::complete::char,
-    combinator::opt,
-    multi::{many1, separated_list1},
-    sequence::{delimited, preceded},
-};
+//! See [`Brex::parse()`] and [`Parser`]
+
+use alloc::{string::String, vec::Vec};
+use core::ops::Range;
 
 use crate::{
-    Brex, Group, Numeric, Suffix,
-    alphabet::{self, BREX_BLOCK, GROUP_BLOCK, GROUP_SUFFIX_SEP, NUMERIC_BLOCK, NUMERIC_LIST_SEP},
+    Alphabetic, Brex, Group, Numeric, Suffix, Text,
+    alphabet::{
+        BREX_BLOCK, GROUP_BLOCK, GROUP_SUFFIX_SEP, NUMERIC_BLOCK, NUMERIC_LIST_SEP,
+        NUMERIC_RANGE_DELIM,
+    },
+    collation, util,
 };
 
 /// Error parsing a brex string
-#[derive(thiserror::Error, Debug)]
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
 pub enum Error {
-    #[error(transparent)]
-    /// Error parsing a numeric suffix
-    ParseNumberError(#[from] std::num::ParseIntError),
-    #[error(transparent)]
-    /// Underlying parser error
-    NomError(#[from] nom::error::Error<String>),
+    #[error("number at byte {position} does not fit in a u32")]
+    /// A number in a numeric block is too large
+    NumberOverflow {
+        /// Byte offset of the number within the input
+        position: usize,
+    },
+    #[error("letters at byte {position} do not fit in a u32")]
+    /// A letter sequence in a numeric block is too long
+    LettersOverflow {
+        /// Byte offset of the letters within the input
+        position: usize,
+    },
+    #[error("malformed brex block at byte {position}")]
+    /// The brex block does not parse, so would be kept as plaintext. Only returned by [`Parser::parse_strict()`]
+    Malformed {
+        /// Byte offset within the input of the furthest point the block parsed up to
+        position: usize,
+    },
+}
+
+/// A group, as byte ranges into the input & into the parser's suffixes
+#[derive(Debug, Clone)]
+struct GroupSpan {
+    prefix: Range<usize>,
+    suffixes: Range<usize>,
+}
+
+/// A suffix, as byte ranges into the input & into the parser's numerics & letters
+#[derive(Debug, Clone)]
+struct SuffixSpan {
+    text: Range<usize>,
+    numerics: Option<Range<usize>>,
+    letters: Option<Range<usize>>,
+}
+
+/// A numeric block, as ranges into the parser's numerics & letters, followed by the position after it
+type BlockSpan = (Option<Range<usize>>, Option<Range<usize>>, usize);
+
+/// A value in a numeric block
+#[derive(Debug, Clone, Copy)]
+enum Value {
+    Number(u32),
+    Letters(u32),
+}
+
+/// A piece of expanded plaintext
+enum Piece<'a> {
+    /// A byte range of the input
+    Input(Range<usize>),
+    /// A number or letter sequence
+    Ascii(&'a str),
+}
+
+/// A reusable brex parser.
+///
+/// Parses into buffers that are kept between calls, so once warmed up, [`Parser::decode_into()`] does not allocate
+/// (beyond growing its output).
+///
+/// ```
+/// # use brex::parse::Parser;
+/// let mut parser = Parser::new();
+/// let mut decoded = String::new();
+/// for line in ["a❮_b{_c,_d{1→2}}❯.bin", "e❮_f{_g,_h}❯"] {
+///     decoded.clear();
+///     parser.decode_into(line, &mut decoded).unwrap();
+/// }
+/// assert_eq!(decoded, "e_f_g_f_h");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Parser {
+    groups: Vec<GroupSpan>,
+    suffixes: Vec<SuffixSpan>,
+    numerics: Vec<Numeric>,
+    letters: Vec<Alphabetic>,
+    /// Scratch space for sorting groups during expansion
+    order: Vec<usize>,
+    /// Scratch space for sorting numbers & letter sequences during expansion
+    numbers: Vec<u32>,
+    /// The furthest position parsing of the brex block failed at
+    furthest: usize,
+    /// Where the last scanned brex block stopped parsing, if it was malformed
+    malformed: Option<usize>,
 }
 
 impl<'a> Brex<'a> {
     /// Parse a brex string.
+    ///
+    /// Whitespace within the brex block is ignored around group prefixes, suffixes & numerics,
+    /// so the pretty form printed by `{:#}` parses to the same IR as the compact form.
+    ///
+    /// Text that is not a well-formed brex block is kept as plaintext, so this only fails if a number is too large.
+    /// To parse many strings, see [`Parser`].
     pub fn parse(input: &'a str) -> Result<Self, Error> {
-        let range = delimited(
-            char(NUMERIC_BLOCK.start),
-            separated_list1(
-                char(NUMERIC_LIST_SEP),
-                (
-                    take_while1(|c: char| c.is_numeric()),
-                    opt(preceded(
-                        char(alphabet::NUMERIC_RANGE_DELIM),
-                        take_while1(|c: char| c.is_numeric()),
-                    )),
-                ),
-            ),
-            char(NUMERIC_BLOCK.end),
-        )
-        .map(|parts| {
-            parts
-                .into_iter()
-                .map(|range: (&str, Option<&str>)| Numeric::try_from(range).unwrap())
-                .collect::<Vec<_>>()
-        });
+        Parser::new().parse(input)
+    }
 
-        let group_suffixes = separated_list1(
-            char(GROUP_SUFFIX_SEP),
-            (
-                is_not(&[GROUP_SUFFIX_SEP, NUMERIC_BLOCK.start, NUMERIC_BLOCK.end][..]),
-                opt(range),
-            ),
-        )
-        .map(|groups| {
-            groups
-                .into_iter()
-                .map(|(suffix, numerics)| Suffix { suffix, numerics })
-                .collect::<Vec<_>>()
-        });
+    /// Parse a brex string, failing with [`Error::Malformed`] instead of keeping a malformed brex block as plaintext.
+    ///
+    /// ```
+    /// # use brex::{Brex, parse::Error};
+    /// assert!(Brex::parse_strict("a❮_b{_c,_d{1→2}}❯.bin").is_ok());
+    /// assert_eq!(
+    ///     Brex::parse_strict("a❮_b{_c,_d{1→}}❯.bin"),
+    ///     Err(Error::Malformed { position: 17 })
+    /// );
+    /// ```
+    pub fn parse_strict(input: &'a str) -> Result<Self, Error> {
+        Parser::new().parse_strict(input)
+    }
+}
+
+impl Parser {
+    /// Create a parser with empty buffers
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse a brex string into its IR. See [`Brex::parse()`].
+    pub fn parse<'a>(&mut self, input: &'a str) -> Result<Brex<'a>, Error> {
+        self.build(input)
+    }
+
+    /// Parse a brex string into its IR, rejecting malformed brex blocks. See [`Brex::parse_strict()`].
+    pub fn parse_strict<'a>(&mut self, input: &'a str) -> Result<Brex<'a>, Error> {
+        let brex = self.build(input)?;
+        match self.malformed {
+            Some(position) => Err(Error::Malformed { position }),
+            None => Ok(brex),
+        }
+    }
 
-        let group_prefix = take_till(|c| c == GROUP_BLOCK.start);
-        let group = (
-            group_prefix,
-            delimited(
-                char(GROUP_BLOCK.start),
-                group_suffixes,
-                char(GROUP_BLOCK.end),
-            ),
-        )
-            .map(|(prefix, suffixes)| Group { prefix, suffixes });
-        let groups = many1(group);
-
-        let preamble = opt(take_till(|c| c == BREX_BLOCK.start));
-        let (input, (preamble, groups)): (_, (_, Option<_>)) = (
-            preamble,
-            opt(delimited(
-                char(alphabet::BREX_BLOCK.start),
-                groups,
-                char(alphabet::BREX_BLOCK.end),
-            )),
-        )
-            .parse(input)
-            .finish()
-            .map_err(|err: nom::error::Error<&str>| err.cloned())?;
-
-        // eprintln!("{input:?}");
-        // eprintln!("{preamble:?}");
-        // eprintln!("{groups:?}");
+    /// Parse a brex byte string into its IR. See [`crate::bytes::parse()`].
+    pub fn parse_bytes<'a>(&mut self, input: &'a [u8]) -> Result<Brex<'a, [u8]>, Error> {
+        self.build(input)
+    }
 
+    fn build<'a, T: Text + ?Sized>(&mut self, input: &'a T) -> Result<Brex<'a, T>, Error> {
+        let (preamble, postamble) = self.scan(input.as_bytes())?;
+        // every span starts & ends on a char boundary
+        let slice = |range: Range<usize>| input.slice(range).unwrap_or(T::empty());
+        let text =
+            |range: Range<usize>| Some(slice(range)).filter(|text| !text.as_bytes().is_empty());
         Ok(Brex {
-            preamble,
-            postamble: match input.is_empty() {
-                true => None,
-                false => Some(input),
-            },
-            groups: groups.unwrap_or_default(),
+            preamble: text(preamble),
+            groups: self
+                .groups
+                .iter()
+                .map(|group| Group {
+                    prefix: slice(group.prefix.clone()),
+                    suffixes: self.suffixes[group.suffixes.clone()]
+                        .iter()
+                        .map(|suffix| Suffix {
+                            suffix: slice(suffix.text.clone()),
+                            numerics: suffix
+                                .numerics
+                                .clone()
+                                .map(|numerics| self.numerics[numerics].to_vec()),
+                            letters: suffix
+                                .letters
+                                .clone()
+                                .map(|letters| self.letters[letters].to_vec()),
+                        })
+                        .collect(),
+                })
+                .collect(),
+            postamble: text(postamble),
         })
     }
+
+    /// Parse and expand a brex string. See [`crate::decode()`].
+    pub fn decode(&mut self, input: &str) -> Result<String, Error> {
+        let mut out = String::with_capacity(input.len());
+        self.decode_into(input, &mut out)?;
+        Ok(out)
+    }
+
+    /// Parse and expand a brex string, appending the plaintext to `out`.
+    pub fn decode_into(&mut self, input: &str, out: &mut String) -> Result<(), Error> {
+        self.expand(input.as_bytes(), |piece| match piece {
+            Piece::Input(range) => out.push_str(&input[range]),
+            Piece::Ascii(ascii) => out.push_str(ascii),
+        })
+    }
+
+    /// Parse and expand a brex byte string. See [`crate::bytes::decode()`].
+    pub fn decode_bytes(&mut self, input: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut out = Vec::with_capacity(input.len());
+        self.decode_bytes_into(input, &mut out)?;
+        Ok(out)
+    }
+
+    /// Parse and expand a brex byte string, appending the plaintext to `out`.
+    pub fn decode_bytes_into(&mut self, input: &[u8], out: &mut Vec<u8>) -> Result<(), Error> {
+        self.expand(input, |piece| match piece {
+            Piece::Input(range) => out.extend_from_slice(&input[range]),
+            Piece::Ascii(ascii) => out.extend_from_slice(ascii.as_bytes()),
+        })
+    }
+
+    /// Parse `input` & emit the pieces of its expansion, in order
+    fn expand(&mut self, input: &[u8], mut emit: impl FnMut(Piece)) -> Result<(), Error> {
+        let (preamble, postamble) = self.scan(input)?;
+        let Self {
+            groups,
+            suffixes,
+            numerics,
+            letters,
+            order,
+            numbers,
+            ..
+        } = self;
+
+        emit(Piece::Input(preamble));
+        order.clear();
+        order.extend(0..groups.len());
+        order.sort_by(|a, b| {
+            collation::cmp(
+                &input[groups[*a].prefix.clone()],
+                &input[groups[*b].prefix.clone()],
+            )
+        });
+        for group in order.iter().map(|i| &groups[*i]) {
+            let prefix = || Piece::Input(group.prefix.clone());
+            for suffix in &suffixes[group.suffixes.clone()] {
+                let text = || Piece::Input(suffix.text.clone());
+                if suffix.numerics.is_none() && suffix.letters.is_none() {
+                    emit(prefix());
+                    emit(text());
+                    continue;
+                }
+                // numbers always sort before letters
+                if let Some(range) = &suffix.numerics {
+                    numbers.clear();
+                    numbers.extend(
+                        numerics[range.clone()]
+                            .iter()
+                            .flat_map(|numeric| numeric.start()..=numeric.end()),
+                    );
+                    numbers.sort_unstable_by(|a, b| collation::cmp_numbers(*a, *b));
+                    for number in numbers.iter() {
+                        emit(prefix());
+                        emit(text());
+                        // only ever ascii digits
+                        let mut digits = [0; 10];
+                        let digits = util::decimal(*number, &mut digits);
+                        emit(Piece::Ascii(
+                            core::str::from_utf8(digits).unwrap_or_default(),
+                        ));
+                    }
+                }
+                if let Some(range) = &suffix.letters {
+                    numbers.clear();
+                    numbers.extend(
+                        letters[range.clone()]
+                            .iter()
+                            .flat_map(|alphabetic| alphabetic.start()..=alphabetic.end()),
+                    );
+                    numbers.sort_unstable_by(|a, b| collation::cmp_letters(*a, *b));
+                    for index in numbers.iter() {
+                        emit(prefix());
+                        emit(text());
+                        emit(Piece::Ascii(util::letters(*index, &mut [0; 7])));
+                    }
+                }
+            }
+        }
+        emit(Piece::Input(postamble));
+        Ok(())
+    }
+
+    /// Parse `input` into the span buffers, returning the preamble & postamble ranges
+    fn scan(&mut self, input: &[u8]) -> Result<(Range<usize>, Range<usize>), Error> {
+        self.groups.clear();
+        self.suffixes.clear();
+        self.numerics.clear();
+        self.letters.clear();
+        self.furthest = 0;
+        self.malformed = None;
+
+        let block = find(input, &[BREX_BLOCK.start]).unwrap_or(input.len());
+        let postamble = match starts_with(&input[block..], BREX_BLOCK.start) {
+            true => match self.block(input, block + BREX_BLOCK.start.len_utf8())? {
+                Some(end) => end,
+                None => {
+                    self.malformed = Some(self.furthest);
+                    block
+                }
+            },
+            // a block end without a start
+            false => {
+                self.malformed = find(input, &[BREX_BLOCK.end]);
+                block
+            }
+        };
+        Ok((0..block, postamble..input.len()))
+    }
+
+    /// Parse the groups of a brex block starting at `pos`, returning the position after the block.
+    ///
+    /// Returns [`None`] if the block is malformed, in which case it is left as postamble.
+    fn block(&mut self, input: &[u8], mut pos: usize) -> Result<Option<usize>, Error> {
+        loop {
+            let (groups, suffixes, numerics, letters) = (
+                self.groups.len(),
+                self.suffixes.len(),
+                self.numerics.len(),
+                self.letters.len(),
+            );
+            match self.group(input, pos)? {
+                Some(end) => pos = end,
+                None => {
+                    self.groups.truncate(groups);
+                    self.suffixes.truncate(suffixes);
+                    self.numerics.truncate(numerics);
+                    self.letters.truncate(letters);
+                    break;
+                }
+            }
+        }
+        pos = skip_whitespace(input, pos);
+        if !self.groups.is_empty() && starts_with(&input[pos..], BREX_BLOCK.end) {
+            return Ok(Some(pos + BREX_BLOCK.end.len_utf8()));
+        }
+        self.fail(pos);
+        self.groups.clear();
+        self.suffixes.clear();
+        self.numerics.clear();
+        self.letters.clear();
+        Ok(None)
+    }
+
+    /// Parse a group starting at `pos`, returning the position after it
+    fn group(&mut self, input: &[u8], pos: usize) -> Result<Option<usize>, Error> {
+        let pos = skip_whitespace(input, pos);
+        let Some(len) = find(&input[pos..], &[GROUP_BLOCK.start]) else {
+            return Ok(None);
+        };
+        let prefix = trim_end(input, pos..pos + len);
+        let mut pos = pos + len + GROUP_BLOCK.start.len_utf8();
+        let first = self.suffixes.len();
+        loop {
+            pos = skip_whitespace(input, pos);
+            let len = find(
+                &input[pos..],
+                &[GROUP_SUFFIX_SEP, NUMERIC_BLOCK.start, NUMERIC_BLOCK.end],
+            )
+            .unwrap_or(input.len() - pos);
+            let text = trim_end(input, pos..pos + len);
+            if text.is_empty() {
+                self.fail(pos);
+                return Ok(None);
+            }
+            pos += len;
+            let (numerics, letters) = match self.numerics(input, pos)? {
+                Some((numerics, letters, end)) => {
+                    pos = end;
+                    (numerics, letters)
+                }
+                None => (None, None),
+            };
+            self.suffixes.push(SuffixSpan {
+                text,
+                numerics,
+                letters,
+            });
+
+            pos = skip_whitespace(input, pos);
+            let rest = &input[pos..];
+            if starts_with(rest, GROUP_SUFFIX_SEP) {
+                pos += GROUP_SUFFIX_SEP.len_utf8();
+            } else if starts_with(rest, GROUP_BLOCK.end) {
+                pos += GROUP_BLOCK.end.len_utf8();
+                break;
+            } else {
+                self.fail(pos);
+                return Ok(None);
+            }
+        }
+        self.groups.push(GroupSpan {
+            prefix,
+            suffixes: first..self.suffixes.len(),
+        });
+        Ok(Some(pos))
+    }
+
+    /// Record that parsing failed at `pos`
+    fn fail(&mut self, pos: usize) {
+        self.furthest = self.furthest.max(pos);
+    }
+
+    /// Parse a numeric block of numbers & letter sequences starting at `pos`,
+    /// returning their ranges in the numerics & letters buffers & the position after it
+    fn numerics(&mut self, input: &[u8], mut pos: usize) -> Result<Option<BlockSpan>, Error> {
+        if !starts_with(&input[pos..], NUMERIC_BLOCK.start) {
+            return Ok(None);
+        }
+        pos += NUMERIC_BLOCK.start.len_utf8();
+        let first = (self.numerics.len(), self.letters.len());
+        // only reported if the whole block parses
+        let mut overflow = None;
+        let mut value = |pos: usize| {
+            let pos = skip_whitespace(input, pos);
+            let (value, end) = match number(input, pos) {
+                Some((value, end)) => {
+                    if value.is_none() {
+                        overflow.get_or_insert(Error::NumberOverflow { position: pos });
+                    }
+                    (Value::Number(value.unwrap_or_default()), end)
+                }
+                None => {
+                    let (value, end) = letters(input, pos)?;
+                    if value.is_none() {
+                        overflow.get_or_insert(Error::LettersOverflow { position: pos });
+                    }
+                    (Value::Letters(value.unwrap_or_default()), end)
+                }
+            };
+            Some((value, skip_whitespace(input, end)))
+        };
+        loop {
+            let Some((start, end)) = value(pos) else {
+                self.fail(skip_whitespace(input, pos));
+                self.numerics.truncate(first.0);
+                self.letters.truncate(first.1);
+                return Ok(None);
+            };
+            pos = end;
+            let range_end = match starts_with(&input[pos..], NUMERIC_RANGE_DELIM) {
+                true => {
+                    let end = pos + NUMERIC_RANGE_DELIM.len_utf8();
+                    let Some(range_end) = value(end) else {
+                        self.fail(skip_whitespace(input, end));
+                        self.numerics.truncate(first.0);
+                        self.letters.truncate(first.1);
+                        return Ok(None);
+                    };
+                    Some(range_end)
+                }
+                false => None,
+            };
+            match (start, range_end) {
+                (Value::Number(start), Some((Value::Number(end), after))) => {
+                    pos = after;
+                    self.numerics.push(Numeric::Range(start, end));
+                }
+                (Value::Letters(start), Some((Value::Letters(end), after))) => {
+                    pos = after;
+                    self.letters.push(Alphabetic::Range(start, end));
+                }
+                (Value::Number(start), None) => self.numerics.push(Numeric::Single(start)),
+                (Value::Letters(start), None) => self.letters.push(Alphabetic::Single(start)),
+                // ranges between a number & letters
+                (_, Some(_)) => {
+                    self.fail(pos);
+                    self.numerics.truncate(first.0);
+                    self.letters.truncate(first.1);
+                    return Ok(None);
+                }
+            }
+
+            let rest = &input[pos..];
+            if starts_with(rest, NUMERIC_LIST_SEP) {
+                pos += NUMERIC_LIST_SEP.len_utf8();
+            } else if starts_with(rest, NUMERIC_BLOCK.end) {
+                pos += NUMERIC_BLOCK.end.len_utf8();
+                break;
+            } else {
+                self.fail(pos);
+                self.numerics.truncate(first.0);
+                self.letters.truncate(first.1);
+                return Ok(None);
+            }
+        }
+        if let Some(overflow) = overflow {
+            return Err(overflow);
+        }
+        let range = |first: usize, len: usize| (first < len).then_some(first..len);
+        Ok(Some((
+            range(first.0, self.numerics.len()),
+            range(first.1, self.letters.len()),
+            pos,
+        )))
+    }
+}
+
+/// Whether `input` starts with the UTF-8 encoding of `ch`
+fn starts_with(input: &[u8], ch: char) -> bool {
+    input.starts_with(ch.encode_utf8(&mut [0; 4]).as_bytes())
+}
+
+/// The byte position of the first of `chars` in `input`
+fn find(input: &[u8], chars: &[char]) -> Option<usize> {
+    (0..input.len()).find(|&pos| chars.iter().any(|ch| starts_with(&input[pos..], *ch)))
+}
+
+/// The char at the start of `input` & its byte length, or [`None`] if it is not valid UTF-8
+fn first_char(input: &[u8]) -> Option<(char, usize)> {
+    let len = match input.first()? {
+        0x00..=0x7f => 1,
+        0xc0..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf7 => 4,
+        _ => return None,
+    };
+    let ch = core::str::from_utf8(input.get(..len)?)
+        .ok()?
+        .chars()
+        .next()?;
+    Some((ch, len))
+}
+
+/// Skip any whitespace starting at `pos`, returning the position after it.
+/// Bytes that aren't valid UTF-8 are never whitespace.
+fn skip_whitespace(input: &[u8], mut pos: usize) -> usize {
+    while let Some((ch, len)) = first_char(&input[pos..]) {
+        if !ch.is_whitespace() {
+            break;
+        }
+        pos += len;
+    }
+    pos
+}
+
+/// Whether `input` neither starts nor ends with whitespace, which the parser would skip around prefixes & suffixes
+pub(crate) fn is_trimmed(input: &[u8]) -> bool {
+    skip_whitespace(input, 0) == 0 && trim_end(input, 0..input.len()).end == input.len()
+}
+
+/// Shrink `range` to exclude trailing whitespace. Bytes that aren't valid UTF-8 are never whitespace.
+pub(crate) fn trim_end(input: &[u8], mut range: Range<usize>) -> Range<usize> {
+    while range.end > range.start {
+        // back up over continuation bytes to the start of the last char
+        let start = (range.start.max(range.end.saturating_sub(4))..range.end)
+            .rev()
+            .find(|&pos| input[pos] & 0xc0 != 0x80)
+            .unwrap_or(range.start);
+        match first_char(&input[start..range.end]) {
+            Some((ch, len)) if start + len == range.end && ch.is_whitespace() => range.end = start,
+            _ => break,
+        }
+    }
+    range
+}
+
+/// Parse the digits starting at `pos`, returning the value ([`None`] if it overflows) & the position after it.
+///
+/// Returns [`None`] if there are no digits.
+fn number(input: &[u8], pos: usize) -> Option<(Option<u32>, usize)> {
+    let len = input[pos..]
+        .iter()
+        .take_while(|b| b.is_ascii_digit())
+        .count();
+    if len == 0 {
+        return None;
+    }
+    let value = input[pos..pos + len].iter().try_fold(0u32, |value, digit| {
+        value.checked_mul(10)?.checked_add(u32::from(digit - b'0'))
+    });
+    Some((value, pos + len))
+}
+
+/// Parse the lowercase letters starting at `pos`, returning their sequence index ([`None`] if it overflows) & the position after it.
+///
+/// Returns [`None`] if there are no letters.
+fn letters(input: &[u8], pos: usize) -> Option<(Option<u32>, usize)> {
+    let len = input[pos..]
+        .iter()
+        .take_while(|b| b.is_ascii_lowercase())
+        .count();
+    if len == 0 {
+        return None;
+    }
+    Some((Alphabetic::index(&input[pos..pos + len]), pos + len))
 }
@@ -0,0 +1,200 @@
+//! Encoding whole lists of file paths
+//!
+//! [`Brex::encode_many()`] encodes a list of paths (e.g every path in a WAD) into a few brex strings,
+//! and [`Brex::expand_many()`] expands them back into the list, sorted.
+//!
+//! Files are grouped by the directories holding them, and each group is encoded as a brex string that expands to
+//! each directory preceded by [`DIRECTORY`], then each file preceded by [`FILE`]. Every directory holds every file,
+//! so shared directory prefixes become groups, numbered sibling folders become numerics (`/skin{0→11}`),
+//! and files that only differ by extension become suffix sets (`/ahri{.dds,.skn}`).
+//! Directories holding some extra files share a string for their common files, and get another for the rest.
+//!
+//! Because of the separators, these strings don't decode to paths on their own:
+//! [`crate::decode()`] & [`Brex::expand()`] give the joined form, e.g `|a/skin0|a/skin1~/base.dds`.
+//!
+//! ```
+//! # use brex::Brex;
+//! let paths = [
+//!     "assets/characters/ahri/skins/skin0/ahri.dds",
+//!     "assets/characters/ahri/skins/skin0/ahri.skn",
+//!     "assets/characters/ahri/skins/skin1/ahri.dds",
+//!     "assets/characters/ahri/skins/skin1/ahri.skn",
+//!     "assets/characters/ahri/skins/skin2/ahri.dds",
+//!     "assets/characters/ahri/skins/skin2/ahri.skn",
+//! ];
+//! let encoded = Brex::encode_many(&paths).unwrap();
+//! assert_eq!(
+//!     encoded,
+//!     ["❮|assets/characters/ahri/skins{/skin{0→2}}~/ahri{.dds,.skn}❯"]
+//! );
+//! assert_eq!(Brex::expand_many(&encoded).unwrap(), paths);
+//! ```
+
+use alloc::{
+    borrow::Cow,
+    collections::BTreeMap,
+    string::{String, ToString as _},
+    vec,
+    vec::Vec,
+};
+
+use crate::{
+    Brex,
+    encode::{self, EncodeOptions, Error},
+    parse::{self, Parser},
+};
+
+/// Precedes each directory in the expansion of a brex string encoded by [`Brex::encode_many()`]
+pub const DIRECTORY: char = '|';
+/// Precedes each file in the expansion of a brex string encoded by [`Brex::encode_many()`].
+///
+/// Sorts after [`DIRECTORY`], so file groups expand after directory groups.
+pub const FILE: char = '~';
+
+/// Directories that all hold the same files, each file starting with its `/`
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Product<'a> {
+    directories: Vec<&'a str>,
+    files: Vec<&'a str>,
+}
+
+impl Brex<'_> {
+    /// Encode a list of paths into a brex string per set of directories holding the same files.
+    ///
+    /// Fails with [`Error::Unrepresentable`] if a path contains [`DIRECTORY`] or [`FILE`],
+    /// or a run of paths doesn't roundtrip, even as plaintext.
+    pub fn encode_many<S: AsRef<str>>(paths: &[S]) -> Result<Vec<String>, Error> {
+        Self::encode_many_with(paths, &EncodeOptions::default())
+    }
+
+    /// [`Brex::encode_many()`], with the given [`EncodeOptions`]. Paths are folded with [`encode::Case::fold()`] first.
+    ///
+    /// [`EncodeOptions::postamble_segments`] is ignored, as extensions are grouped on instead.
+    pub fn encode_many_with<S: AsRef<str>>(
+        paths: &[S],
+        options: &EncodeOptions,
+    ) -> Result<Vec<String>, Error> {
+        let paths = paths
+            .iter()
+            .map(|path| match path.as_ref().contains([DIRECTORY, FILE]) {
+                true => Err(Error::Unrepresentable(path.as_ref().to_string())),
+                false => Ok(options.case.fold(path.as_ref())),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut parser = Parser::new();
+        products(&paths)
+            .iter()
+            .map(|product| encode_product(product, options, &mut parser))
+            .collect()
+    }
+
+    /// Expand brex strings encoded by [`Brex::encode_many()`] back into their sorted list of paths
+    pub fn expand_many<S: AsRef<str>>(encoded: &[S]) -> Result<Vec<String>, parse::Error> {
+        let mut parser = Parser::new();
+        let mut decoded = String::new();
+        let mut paths = Vec::new();
+        for encoded in encoded {
+            decoded.clear();
+            parser.decode_into(encoded.as_ref(), &mut decoded)?;
+            let (directories, files) =
+                decoded.split_at(decoded.find(FILE).unwrap_or(decoded.len()));
+            for directory in directories.split(DIRECTORY).skip(1) {
+                paths.extend(
+                    files
+                        .split(FILE)
+                        .skip(1)
+                        .map(|file| [directory, file].concat()),
+                );
+            }
+        }
+        paths.sort_unstable();
+        Ok(paths)
+    }
+}
+
+/// Split a path into its directory, and the rest from its last `/`
+fn split_directory(path: &str) -> (&str, &str) {
+    path.split_at(path.rfind('/').unwrap_or(0))
+}
+
+/// Group files by the (sorted) directories holding them, in order of each group's first file
+fn products<'a>(paths: &'a [Cow<'a, str>]) -> Vec<Product<'a>> {
+    let mut files = Vec::<(&str, Vec<&str>)>::new();
+    let mut indices = BTreeMap::new();
+    for (directory, file) in paths.iter().map(|path| split_directory(path)) {
+        let index = *indices.entry(file).or_insert_with(|| {
+            files.push((file, Vec::new()));
+            files.len() - 1
+        });
+        files[index].1.push(directory);
+    }
+
+    let mut products = Vec::<Product>::new();
+    let mut indices = BTreeMap::<Vec<&str>, usize>::new();
+    for (file, mut directories) in files {
+        directories.sort_unstable();
+        match indices.get(&directories) {
+            Some(&index) => products[index].files.push(file),
+            None => {
+                indices.insert(directories.clone(), products.len());
+                products.push(Product {
+                    directories,
+                    files: vec![file],
+                });
+            }
+        }
+    }
+    products
+}
+
+/// Encode a product, keeping the shortest encoding that roundtrips
+fn encode_product(
+    product: &Product,
+    options: &EncodeOptions,
+    parser: &mut Parser,
+) -> Result<String, Error> {
+    let mut joined = String::new();
+    for directory in &product.directories {
+        joined.push(DIRECTORY);
+        joined.push_str(directory);
+    }
+    for file in &product.files {
+        joined.push(FILE);
+        joined.push_str(file);
+    }
+
+    // directories group on their parent, files on their name
+    let mut parts = Vec::with_capacity(2 * (product.directories.len() + product.files.len()));
+    let mut rest = joined.as_str();
+    while let Some(separator) = rest.chars().next() {
+        let len = rest[separator.len_utf8()..]
+            .find([DIRECTORY, FILE])
+            .map_or(rest.len(), |end| end + separator.len_utf8());
+        let (entry, next) = rest.split_at(len);
+        let split = match separator {
+            DIRECTORY => '/',
+            _ => '.',
+        };
+        let at = entry.rfind(split).unwrap_or(separator.len_utf8());
+        let (head, tail) = entry.split_at(at);
+        parts.extend([head, tail]);
+        rest = next;
+    }
+
+    let no_numerics = EncodeOptions {
+        numerics: false,
+        letters: false,
+        ..options.clone()
+    };
+    [options, &no_numerics]
+        .into_iter()
+        .map(|options| encode::encode_parts(joined.as_str(), &parts, options).to_string())
+        .chain([Brex::empty(joined.as_str()).to_string()])
+        .filter(|encoded| {
+            parser
+                .decode(encoded)
+                .is_ok_and(|decoded| decoded == joined)
+        })
+        .min_by_key(|encoded| options.optimise_for.of(encoded))
+        .ok_or_else(|| Error::Unrepresentable(joined.clone()))
+}
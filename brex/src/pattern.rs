@@ -0,0 +1,522 @@
+//! Matching names against brex patterns
+//!
+//! Used as a pattern, a brex matches its preamble, followed by any *one* of its group's expanded components, followed by its postamble.
+//! For example, `data/characters/ahri/❮skins/{root,skin{0→2}}❯.bin` matches `data/characters/ahri/skins/root.bin` & `skin0.bin` through `skin2.bin`.
+//! Letter sequences match the same way, so `❮variant{_{a→c}}❯` matches `variant_a` through `variant_c`.
+//!
+//! See [`Brex::compile()`]
+
+use alloc::{format, string::String, vec, vec::Vec};
+use core::fmt::Write as _;
+
+use crate::{Alphabetic, Brex, Numeric, util::letters};
+
+/// A single alternative of a [`Pattern`]: a group prefix & one of its suffixes
+#[derive(Debug, Clone)]
+struct Alternative<'a> {
+    prefix: &'a str,
+    suffix: &'a str,
+    numerics: Option<&'a [Numeric]>,
+    letters: Option<&'a [Alphabetic]>,
+}
+
+impl Alternative<'_> {
+    fn literal(&self, pos: usize) -> Option<u8> {
+        let prefix = self.prefix.as_bytes();
+        match pos < prefix.len() {
+            true => Some(prefix[pos]),
+            false => self.suffix.as_bytes().get(pos - prefix.len()).copied(),
+        }
+    }
+
+    fn literal_len(&self) -> usize {
+        self.prefix.len() + self.suffix.len()
+    }
+
+    fn max_number(&self) -> u64 {
+        self.numerics
+            .into_iter()
+            .flatten()
+            .map(|numeric| numeric.end() as u64)
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn contains(&self, number: u64) -> bool {
+        self.numerics
+            .into_iter()
+            .flatten()
+            .any(|numeric| (numeric.start() as u64..=numeric.end() as u64).contains(&number))
+    }
+
+    fn max_letters(&self) -> u64 {
+        self.letters
+            .into_iter()
+            .flatten()
+            .map(|alphabetic| alphabetic.end() as u64)
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn contains_letters(&self, index: u64) -> bool {
+        self.letters.into_iter().flatten().any(|alphabetic| {
+            (alphabetic.start() as u64..=alphabetic.end() as u64).contains(&index)
+        })
+    }
+
+    /// Each value this alternative ends with, or a single [`None`] if it has no numeric block
+    fn values(&self) -> impl Iterator<Item = Option<Value>> + '_ {
+        let plain = (self.numerics.is_none() && self.letters.is_none()).then_some(None);
+        let numbers = self
+            .numerics
+            .into_iter()
+            .flatten()
+            .flat_map(|numeric| numeric.start()..=numeric.end())
+            .map(|number| Some(Value::Number(number)));
+        let letters = self
+            .letters
+            .into_iter()
+            .flatten()
+            .flat_map(|alphabetic| alphabetic.start()..=alphabetic.end())
+            .map(|index| Some(Value::Letters(index)));
+        plain.into_iter().chain(numbers).chain(letters)
+    }
+
+    fn count(&self) -> u64 {
+        // descending ranges yield no values
+        let numbers = self.numerics.into_iter().flatten().map(|numeric| {
+            u64::from(numeric.end())
+                .checked_sub(u64::from(numeric.start()))
+                .map_or(0, |d| d + 1)
+        });
+        let letters = self.letters.into_iter().flatten().map(|alphabetic| {
+            u64::from(alphabetic.end())
+                .checked_sub(u64::from(alphabetic.start()))
+                .map_or(0, |d| d + 1)
+        });
+        match self.numerics.is_none() && self.letters.is_none() {
+            true => 1,
+            false => numbers.chain(letters).sum(),
+        }
+    }
+}
+
+/// A value from a numeric block
+#[derive(Debug, Clone, Copy)]
+enum Value {
+    Number(u32),
+    Letters(u32),
+}
+
+/// A brex compiled for matching, created via [`Brex::compile()`].
+///
+/// With the `fst` feature, this implements `fst::Automaton`, so can be passed to `fst::Set::search` to stream all matching keys.
+#[derive(Debug, Clone)]
+pub struct Pattern<'a> {
+    preamble: &'a str,
+    alternatives: Vec<Alternative<'a>>,
+    postamble: &'a str,
+}
+
+/// A position within a [`Pattern`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Thread {
+    Preamble(usize),
+    Literal {
+        alt: usize,
+        pos: usize,
+    },
+    Number {
+        alt: usize,
+        value: u64,
+        digits: u8,
+    },
+    /// `value` is one more than the sequence index, so that an empty sequence is `0`
+    Letters {
+        alt: usize,
+        value: u64,
+    },
+    Postamble(usize),
+}
+
+/// The state of a [`Pattern`] match. Empty once the input can no longer match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct State(Vec<Thread>);
+
+impl Brex<'_> {
+    /// Compile this brex into a [`Pattern`], for matching names without expanding every candidate.
+    pub fn compile(&self) -> Pattern<'_> {
+        Pattern {
+            preamble: self.preamble.unwrap_or_default(),
+            alternatives: self
+                .groups
+                .iter()
+                .flat_map(|group| {
+                    group.suffixes.iter().map(|suffix| Alternative {
+                        prefix: group.prefix,
+                        suffix: suffix.suffix,
+                        numerics: suffix.numerics.as_deref(),
+                        letters: suffix.letters.as_deref(),
+                    })
+                })
+                .collect(),
+            postamble: self.postamble.unwrap_or_default(),
+        }
+    }
+
+    /// Convert this brex into an equivalent, anchored regex. See [`Pattern::to_regex()`].
+    pub fn to_regex(&self) -> String {
+        self.compile().to_regex()
+    }
+}
+
+impl Pattern<'_> {
+    /// The state before any input
+    pub fn start(&self) -> State {
+        let mut threads = vec![];
+        self.push(&mut threads, Thread::Preamble(0));
+        State(threads)
+    }
+
+    /// Advance `state` by one byte of input
+    pub fn accept(&self, state: &State, byte: u8) -> State {
+        let mut threads = Vec::with_capacity(state.0.len());
+        for thread in &state.0 {
+            let next = match *thread {
+                Thread::Preamble(pos) => (self.preamble.as_bytes().get(pos) == Some(&byte))
+                    .then_some(Thread::Preamble(pos + 1)),
+                Thread::Literal { alt, pos } => (self.alternatives[alt].literal(pos) == Some(byte))
+                    .then_some(Thread::Literal { alt, pos: pos + 1 }),
+                Thread::Number { alt, value, digits } => match byte {
+                    // numbers are never displayed with leading zeros
+                    b'0'..=b'9' if !(digits > 0 && value == 0) => {
+                        let value = value * 10 + u64::from(byte - b'0');
+                        (value <= self.alternatives[alt].max_number()).then_some(Thread::Number {
+                            alt,
+                            value,
+                            digits: digits + 1,
+                        })
+                    }
+                    _ => None,
+                },
+                Thread::Letters { alt, value } => match byte {
+                    b'a'..=b'z' => {
+                        let value = value * 26 + u64::from(byte - b'a' + 1);
+                        (value <= self.alternatives[alt].max_letters() + 1)
+                            .then_some(Thread::Letters { alt, value })
+                    }
+                    _ => None,
+                },
+                Thread::Postamble(pos) => (self.postamble.as_bytes().get(pos) == Some(&byte))
+                    .then_some(Thread::Postamble(pos + 1)),
+            };
+            if let Some(next) = next {
+                self.push(&mut threads, next);
+            }
+        }
+        threads.sort_unstable();
+        threads.dedup();
+        State(threads)
+    }
+
+    /// Whether the input so far matches
+    pub fn is_match(&self, state: &State) -> bool {
+        state.0.contains(&Thread::Postamble(self.postamble.len()))
+    }
+
+    /// Whether more input could still lead to a match
+    pub fn can_match(&self, state: &State) -> bool {
+        !state.0.is_empty()
+    }
+
+    /// Whether `input` matches this pattern
+    pub fn matches(&self, input: &str) -> bool {
+        let mut state = self.start();
+        for byte in input.bytes() {
+            state = self.accept(&state, byte);
+            if !self.can_match(&state) {
+                return false;
+            }
+        }
+        self.is_match(&state)
+    }
+
+    /// Every name this pattern matches, expanded lazily in the order the pattern lists them.
+    ///
+    /// A pattern without groups only matches its preamble & postamble.
+    pub fn candidates(&self) -> impl Iterator<Item = String> + '_ {
+        let plain = self
+            .alternatives
+            .is_empty()
+            .then(|| format!("{}{}", self.preamble, self.postamble));
+        let candidates = self.alternatives.iter().flat_map(move |alt| {
+            alt.values().map(move |value| {
+                let mut candidate = String::from(self.preamble);
+                candidate.push_str(alt.prefix);
+                candidate.push_str(alt.suffix);
+                match value {
+                    // writing to a `String` is infallible
+                    Some(Value::Number(number)) => _ = write!(candidate, "{number}"),
+                    Some(Value::Letters(index)) => candidate.push_str(letters(index, &mut [0; 7])),
+                    None => {}
+                }
+                candidate.push_str(self.postamble);
+                candidate
+            })
+        });
+        plain.into_iter().chain(candidates)
+    }
+
+    /// The number of names [`Self::candidates()`] yields
+    pub fn candidate_count(&self) -> u64 {
+        match self.alternatives.is_empty() {
+            true => 1,
+            false => self.alternatives.iter().map(Alternative::count).sum(),
+        }
+    }
+
+    /// Push `thread`, along with every thread reachable from it without consuming input
+    fn push(&self, threads: &mut Vec<Thread>, thread: Thread) {
+        threads.push(thread);
+        match thread {
+            Thread::Preamble(pos) if pos == self.preamble.len() => {
+                if self.alternatives.is_empty() {
+                    self.push(threads, Thread::Postamble(0));
+                }
+                for alt in 0..self.alternatives.len() {
+                    self.push(threads, Thread::Literal { alt, pos: 0 });
+                }
+            }
+            Thread::Literal { alt, pos } if pos == self.alternatives[alt].literal_len() => {
+                let alternative = &self.alternatives[alt];
+                if alternative.numerics.is_some() {
+                    self.push(
+                        threads,
+                        Thread::Number {
+                            alt,
+                            value: 0,
+                            digits: 0,
+                        },
+                    );
+                }
+                if alternative.letters.is_some() {
+                    self.push(threads, Thread::Letters { alt, value: 0 });
+                }
+                if alternative.numerics.is_none() && alternative.letters.is_none() {
+                    self.push(threads, Thread::Postamble(0));
+                }
+            }
+            Thread::Number { alt, value, digits }
+                if digits > 0 && self.alternatives[alt].contains(value) =>
+            {
+                self.push(threads, Thread::Postamble(0))
+            }
+            Thread::Letters { alt, value }
+                if value > 0 && self.alternatives[alt].contains_letters(value - 1) =>
+            {
+                self.push(threads, Thread::Postamble(0))
+            }
+            _ => {}
+        }
+    }
+
+    /// Convert this pattern into an equivalent regex, anchored with `^` & `$`.
+    ///
+    /// Numeric & letter ranges are converted to character classes, rather than listing every value.
+    pub fn to_regex(&self) -> String {
+        let mut regex = String::from("^");
+        escape_into(&mut regex, self.preamble);
+        if !self.alternatives.is_empty() {
+            regex.push_str("(?:");
+            for (i, alt) in self.alternatives.iter().enumerate() {
+                if i > 0 {
+                    regex.push('|');
+                }
+                escape_into(&mut regex, alt.prefix);
+                escape_into(&mut regex, alt.suffix);
+                if alt.numerics.is_some() || alt.letters.is_some() {
+                    let numbers = alt
+                        .numerics
+                        .into_iter()
+                        .flatten()
+                        .flat_map(|numeric| range_regex(numeric.start(), numeric.end()));
+                    let letters =
+                        alt.letters.into_iter().flatten().flat_map(|alphabetic| {
+                            letters_regex(alphabetic.start(), alphabetic.end())
+                        });
+                    let ranges = numbers.chain(letters).collect::<Vec<_>>();
+                    let _ = write!(regex, "(?:{})", ranges.join("|"));
+                }
+            }
+            regex.push(')');
+        }
+        escape_into(&mut regex, self.postamble);
+        regex.push('$');
+        regex
+    }
+}
+
+/// Escape regex meta characters
+fn escape_into(regex: &mut String, text: &str) {
+    for ch in text.chars() {
+        if matches!(
+            ch,
+            '\\' | '.'
+                | '+'
+                | '*'
+                | '?'
+                | '('
+                | ')'
+                | '|'
+                | '['
+                | ']'
+                | '{'
+                | '}'
+                | '^'
+                | '$'
+                | '#'
+                | '&'
+                | '-'
+                | '~'
+        ) {
+            regex.push('\\');
+        }
+        regex.push(ch);
+    }
+}
+
+/// Regex alternatives matching exactly the (non zero-padded) numbers from `start` to `end`, inclusive
+fn range_regex(start: u32, end: u32) -> Vec<String> {
+    let mut alternatives = vec![];
+    let mut width_start = start;
+    while width_start <= end {
+        let digits = width_start.checked_ilog10().unwrap_or_default() + 1;
+        // the largest number with the same number of digits
+        let width_end = 10u64.pow(digits) - 1;
+        let width_end = end.min(u32::try_from(width_end).unwrap_or(u32::MAX));
+        same_width_regex(
+            format!("{width_start}").as_bytes(),
+            format!("{width_end}").as_bytes(),
+            (b'0', b'9'),
+            &mut String::new(),
+            &mut alternatives,
+        );
+        match width_end.checked_add(1) {
+            Some(next) => width_start = next,
+            None => break,
+        }
+    }
+    alternatives
+}
+
+/// Regex alternatives matching exactly the letter sequences from index `start` to `end`, inclusive
+fn letters_regex(start: u32, end: u32) -> Vec<String> {
+    let mut alternatives = vec![];
+    // the first index of each length of sequence: `a`, `aa`, `aaa`, …
+    let mut width_start = 0u64;
+    let mut width_len = 26u64;
+    while width_start <= u64::from(end) {
+        let width_end = width_start + width_len - 1;
+        let (from, to) = (
+            width_start.max(u64::from(start)),
+            width_end.min(u64::from(end)),
+        );
+        if from <= to {
+            // both are within `start..=end`, so fit in a `u32`
+            same_width_regex(
+                letters(from as u32, &mut [0; 7]).as_bytes(),
+                letters(to as u32, &mut [0; 7]).as_bytes(),
+                (b'a', b'z'),
+                &mut String::new(),
+                &mut alternatives,
+            );
+        }
+        width_start += width_len;
+        width_len *= 26;
+    }
+    alternatives
+}
+
+/// Regex alternatives matching every string from `start` to `end` over the contiguous `alphabet`, where both are the same length
+fn same_width_regex(
+    start: &[u8],
+    end: &[u8],
+    alphabet: (u8, u8),
+    prefix: &mut String,
+    out: &mut Vec<String>,
+) {
+    let (min, max) = alphabet;
+    let (Some((&first, start_rest)), Some((&last, end_rest))) =
+        (start.split_first(), end.split_first())
+    else {
+        out.push(prefix.clone());
+        return;
+    };
+    if first == last {
+        prefix.push(first as char);
+        same_width_regex(start_rest, end_rest, alphabet, prefix, out);
+        prefix.pop();
+        return;
+    }
+
+    let any = |len: usize| match len {
+        0 => String::new(),
+        1 => format!("[{}-{}]", min as char, max as char),
+        len => format!("[{}-{}]{{{len}}}", min as char, max as char),
+    };
+    let class = |from: u8, to: u8| match from == to {
+        true => String::from(from as char),
+        false => format!("[{}-{}]", from as char, to as char),
+    };
+
+    // everything from `start` up to the end of its leading digit
+    let mut middle_from = first;
+    if start_rest.iter().any(|digit| *digit != min) {
+        prefix.push(first as char);
+        let maxes = vec![max; start_rest.len()];
+        same_width_regex(start_rest, &maxes, alphabet, prefix, out);
+        prefix.pop();
+        middle_from += 1;
+    }
+    // from the start of `end`'s leading digit up to `end`
+    let mut middle_to = last;
+    let end_partial = end_rest.iter().any(|digit| *digit != max);
+    if end_partial {
+        middle_to -= 1;
+    }
+    // every leading digit in between, followed by any digits
+    if middle_from <= middle_to {
+        out.push(format!(
+            "{prefix}{}{}",
+            class(middle_from, middle_to),
+            any(start_rest.len())
+        ));
+    }
+    if end_partial {
+        prefix.push(last as char);
+        let mins = vec![min; end_rest.len()];
+        same_width_regex(&mins, end_rest, alphabet, prefix, out);
+        prefix.pop();
+    }
+}
+
+#[cfg(any(feature = "fst", test))]
+impl fst::Automaton for Pattern<'_> {
+    type State = State;
+
+    fn start(&self) -> State {
+        Pattern::start(self)
+    }
+
+    fn is_match(&self, state: &State) -> bool {
+        Pattern::is_match(self, state)
+    }
+
+    fn can_match(&self, state: &State) -> bool {
+        Pattern::can_match(self, state)
+    }
+
+    fn accept(&self, state: &State, byte: u8) -> State {
+        Pattern::accept(self, state, byte)
+    }
+}
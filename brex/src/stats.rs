@@ -0,0 +1,127 @@
+//! Compression statistics of brex strings
+//!
+//! See [`Brex::stats()`]
+
+use alloc::string::ToString as _;
+
+use crate::{Alphabetic, Brex, Numeric, util::split_inclusive_start};
+
+/// Which `_`-separated parts of a name the encoder used as group prefixes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parity {
+    /// The preamble has an even number of parts, so groups start on an even part
+    Even,
+    /// The preamble has an odd number of parts, so groups start on an odd part
+    Odd,
+}
+
+impl core::fmt::Display for Parity {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Parity::Even => "even",
+            Parity::Odd => "odd",
+        })
+    }
+}
+
+/// Length of a string, in [`char`]s & UTF-8 bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Len {
+    /// Length in [`char`]s
+    pub chars: usize,
+    /// Length in UTF-8 bytes
+    pub bytes: usize,
+}
+
+impl Len {
+    /// Measure the given string
+    pub fn of(s: &str) -> Self {
+        Self {
+            chars: s.chars().count(),
+            bytes: s.len(),
+        }
+    }
+}
+
+/// Statistics about a [`Brex`], created via [`Brex::stats()`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stats {
+    /// Length of the expanded plaintext
+    pub input: Len,
+    /// Length of the displayed brex string
+    pub output: Len,
+    /// Number of groups
+    pub groups: usize,
+    /// Number of suffixes, across all groups
+    pub suffixes: usize,
+    /// Number of [`Numeric::Range`]s & [`Alphabetic::Range`]s
+    pub ranges: usize,
+    /// Number of values collapsed into [`Numeric::Range`]s & [`Alphabetic::Range`]s
+    pub ranged_values: usize,
+    /// Number of `_`-separated parts in the preamble
+    pub preamble_parts: usize,
+    /// Which parts the groups were formed from
+    pub parity: Parity,
+}
+
+impl Stats {
+    /// Output length as a fraction of the input length, in [`char`]s
+    pub fn char_ratio(&self) -> f64 {
+        ratio(self.output.chars, self.input.chars)
+    }
+
+    /// Output length as a fraction of the input length, in bytes
+    pub fn byte_ratio(&self) -> f64 {
+        ratio(self.output.bytes, self.input.bytes)
+    }
+}
+
+fn ratio(output: usize, input: usize) -> f64 {
+    match input {
+        0 => 1.0,
+        input => output as f64 / input as f64,
+    }
+}
+
+impl Brex<'_> {
+    /// Calculate compression statistics for this brex.
+    pub fn stats(&self) -> Stats {
+        let suffixes = || self.groups.iter().flat_map(|group| &group.suffixes);
+        let ranges = || {
+            let numerics = suffixes()
+                .flat_map(|suffix| suffix.numerics.iter().flatten())
+                .filter_map(|numeric| match *numeric {
+                    Numeric::Range(start, end) => Some((start, end)),
+                    Numeric::Single(_) => None,
+                });
+            let letters = suffixes()
+                .flat_map(|suffix| suffix.letters.iter().flatten())
+                .filter_map(|alphabetic| match *alphabetic {
+                    Alphabetic::Range(start, end) => Some((start, end)),
+                    Alphabetic::Single(_) => None,
+                });
+            numerics.chain(letters)
+        };
+        let preamble_parts = self
+            .preamble
+            .map(|preamble| split_inclusive_start(preamble, '_').count())
+            .unwrap_or_default();
+
+        Stats {
+            input: Len::of(&self.expand()),
+            output: Len::of(&self.to_string()),
+            groups: self.groups.len(),
+            suffixes: self.groups.iter().map(|group| group.suffixes.len()).sum(),
+            ranges: ranges().count(),
+            ranged_values: ranges()
+                // descending ranges parse, but expand to nothing
+                .map(|(start, end)| end.checked_sub(start).map_or(0, |len| len as usize + 1))
+                .sum(),
+            preamble_parts,
+            parity: match preamble_parts % 2 {
+                0 => Parity::Even,
+                _ => Parity::Odd,
+            },
+        }
+    }
+}
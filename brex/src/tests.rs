@@ -1,3 +1,6 @@
+#[path = "../benches/parse/nom_parser.rs"]
+mod nom_parser;
+
 mod roundtrip {
     use test_each_file::test_each_file;
 
@@ -19,3 +22,1387 @@ mod roundtrip {
         }
     }
 }
+
+mod builder {
+    use crate::{Brex, Numeric, builder::Error};
+
+    #[test]
+    fn builds_parseable() {
+        let brex = Brex::builder()
+            .preamble("aatrox")
+            .group("_skins")
+            .suffix("_root")
+            .numbers("_skin", [Numeric::Range(0, 2), Numeric::Single(5)])
+            .postamble(".bin")
+            .build()
+            .unwrap();
+        let displayed = brex.to_string();
+        assert_eq!(displayed, "aatrox❮_skins{_root,_skin{0→2,5}}❯.bin");
+        assert_eq!(Brex::parse(&displayed).unwrap(), brex);
+    }
+
+    #[test]
+    fn empty_preamble() {
+        let brex = Brex::builder()
+            .preamble("")
+            .group("_skins")
+            .suffix("_root")
+            .build()
+            .unwrap();
+        assert_eq!(brex.preamble, None);
+        let parsed = Brex::parse("❮_skins{_root}❯").unwrap();
+        assert_eq!(parsed.preamble, None);
+        assert_eq!(parsed, brex);
+    }
+
+    #[test]
+    fn rejects_unrepresentable() {
+        assert!(matches!(
+            Brex::builder().group("a{b").suffix("c").build(),
+            Err(Error::ReservedChar { ch: '{', .. })
+        ));
+        assert_eq!(
+            Brex::builder().group("a").build(),
+            Err(Error::EmptyGroup("a".into()))
+        );
+        assert_eq!(
+            Brex::builder().suffix("a").group("b").suffix("c").build(),
+            Err(Error::NoGroup("a".into()))
+        );
+        assert_eq!(
+            Brex::builder().group("a").numbers("b", []).build(),
+            Err(Error::EmptyNumerics("b".into()))
+        );
+        assert_eq!(
+            Brex::builder()
+                .group("a")
+                .numbers("b", [Numeric::Range(3, 1)])
+                .build(),
+            Err(Error::InvalidRange(3, 1))
+        );
+        assert_eq!(
+            Brex::builder().preamble("a").postamble(".bin").build(),
+            Err(Error::PostambleWithoutGroups)
+        );
+        assert!(matches!(
+            Brex::builder().group("a").suffix("b ").build(),
+            Err(Error::Whitespace { .. })
+        ));
+    }
+}
+
+mod checked {
+    use crate::{decode, encode::Fallback, encode_checked};
+
+    fn assert_checked(input: &str, fallback: Option<Fallback>) {
+        let (encoded, taken) = encode_checked(input).unwrap();
+        assert_eq!(decode(&encoded).unwrap(), input);
+        assert_eq!(taken, fallback, "{input:?} -> {encoded:?}");
+    }
+
+    #[test]
+    fn verified() {
+        assert_checked("aatrox_skins_skin0_skins_skin1.bin", None);
+    }
+
+    #[test]
+    fn digits_followed_by_letters() {
+        assert_checked("x_skins_skin1a_skins_skin2_skins_skin3", None);
+    }
+
+    #[test]
+    fn unsorted_numerics() {
+        assert_checked(
+            "x_skins_skin2_skins_skin1_skins_skin3",
+            Some(Fallback::NoNumerics),
+        );
+    }
+
+    #[test]
+    fn whitespace_at_part_edges() {
+        for input in [
+            "x_aaaaaaa _ppppp_aaaaaaa _qqqqq.bin",
+            "x_aaaaaaa_ ppppp_aaaaaaa_ qqqqq.bin",
+            "x_skins_skin 1_skins_skin 2_skins_skin 3.bin",
+            "x_skins_skin1 _skins_skin2 _skins_skin3 .bin",
+        ] {
+            assert_eq!(decode(&crate::encode(input).unwrap()).unwrap(), input);
+            assert_checked(input, None);
+        }
+    }
+
+    #[test]
+    fn unpaired_prefix() {
+        assert_checked("a_x_b_x_a_y.bin", None);
+    }
+
+    #[test]
+    fn unsorted_groups() {
+        // groups out of collation order are never encoded, so no fallback is needed
+        assert_checked("p_bbbbbb_x_bbbbbb_y_aaaaaa_x_aaaaaa_y", None);
+    }
+}
+
+mod collation {
+    use crate::{
+        collation::{self, Key, Value},
+        decode, encode_checked,
+    };
+
+    #[test]
+    fn prefixes() {
+        let mut prefixes = ["_skin", "_skins", "_base", "_skin1"];
+        collation::sort(&mut prefixes);
+        assert_eq!(prefixes, ["_base", "_skin1", "_skins", "_skin"]);
+        assert!(Key::new(b"_skins") < Key::new(b"_skin"));
+    }
+
+    #[test]
+    fn values() {
+        assert!(Value::Number(10) < Value::Number(9));
+        assert!(Value::Number(99) < Value::Letters(0));
+        assert!(Value::Letters(26) < Value::Letters(1)); // aa < b
+    }
+
+    #[test]
+    fn matches_expansion() {
+        let input = "x_skins_skin1_skins_skin10_skins_skin2_superfanvariant_a_superfan_a.bin";
+        let (encoded, fallback) = encode_checked(input).unwrap();
+        assert_eq!(fallback, None);
+        assert_eq!(decode(&encoded).unwrap(), input);
+    }
+}
+
+mod options {
+    use std::borrow::Cow;
+
+    use crate::{
+        Brex, decode,
+        encode::{Case, EncodeOptions, Error, Length},
+        encode_with,
+    };
+
+    const INPUT: &str = "aatrox_base_a_base_b_skins_root_skins_skin0_skins_skin1_skins_skin2.bin";
+
+    fn assert_encodes(options: EncodeOptions, expected: &str) {
+        let encoded = encode_with(INPUT, &options).unwrap();
+        assert_eq!(encoded, expected);
+        assert_eq!(decode(&encoded).unwrap(), INPUT);
+    }
+
+    #[test]
+    fn default() {
+        assert_encodes(
+            EncodeOptions::default(),
+            "aatrox❮_base{_a,_b}_skins{_root,_skin{0→2}}❯.bin",
+        );
+    }
+
+    #[test]
+    fn min_group_size() {
+        assert_encodes(
+            EncodeOptions {
+                min_group_size: 3,
+                ..Default::default()
+            },
+            "aatrox_base_a_base_b❮_skins{_root,_skin{0→2}}❯.bin",
+        );
+    }
+
+    #[test]
+    fn min_group_size_middle() {
+        // `_rig_root` is a run of one between two runs of four, so it can't be a group of its own
+        let input = "aatrox_animations_skin1_animations_skin2_animations_skin3_animations_skin4\
+                     _rig_root_skins_skin1_skins_skin2_skins_skin3_skins_skin4.bin";
+        for min_group_size in [2, 3] {
+            let options = EncodeOptions {
+                min_group_size,
+                ..Default::default()
+            };
+            let encoded = encode_with(input, &options).unwrap();
+            assert_eq!(
+                encoded,
+                "aatrox❮_animations{_skin{1→4}}❯_rig_root_skins_skin1_skins_skin2_skins_skin3_skins_skin4.bin"
+            );
+            assert_eq!(decode(&encoded).unwrap(), input);
+        }
+        let options = EncodeOptions {
+            min_group_size: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            encode_with(input, &options).unwrap(),
+            "aatrox❮_animations{_skin{1→4}}_rig{_root}_skins{_skin{1→4}}❯.bin"
+        );
+    }
+
+    #[test]
+    fn no_numerics() {
+        assert_encodes(
+            EncodeOptions {
+                numerics: false,
+                ..Default::default()
+            },
+            "aatrox❮_base{_a,_b}_skins{_root,_skin0,_skin1,_skin2}❯.bin",
+        );
+    }
+
+    #[test]
+    fn postamble_segments() {
+        let options = EncodeOptions {
+            postamble_segments: 2,
+            ..Default::default()
+        };
+        let input = "a_bbbbbb_x_bbbbbb_y.c.bin";
+        assert_eq!(
+            encode_with(input, &options).unwrap(),
+            "a❮_bbbbbb{_x,_y}❯.c.bin"
+        );
+    }
+
+    #[test]
+    fn optimise_for_bytes() {
+        let input = "a_bbbbbb_x_bbbbbb_y";
+        let chars = encode_with(input, &EncodeOptions::default()).unwrap();
+        assert_eq!(chars, "a❮_bbbbbb{_x,_y}❯");
+        let bytes = EncodeOptions {
+            optimise_for: Length::Bytes,
+            ..Default::default()
+        };
+        assert_eq!(encode_with(input, &bytes).unwrap(), input);
+    }
+
+    #[test]
+    fn case() {
+        let input = "Aatrox_Skins_Skin1_skins_skin2_SKINS_Skin3.bin";
+        assert_eq!(
+            encode_with(input, &EncodeOptions::default()).unwrap(),
+            input
+        );
+        let lower = EncodeOptions {
+            case: Case::Lower,
+            ..Default::default()
+        };
+        let encoded = encode_with(input, &lower).unwrap();
+        assert_eq!(encoded, "aatrox❮_skins{_skin{1→3}}❯.bin");
+        assert_eq!(decode(&encoded).unwrap(), input.to_lowercase());
+        assert!(matches!(Case::Lower.fold("aatrox"), Cow::Borrowed(_)));
+        assert!(matches!(
+            Brex::encode_with(input, &lower),
+            Err(Error::Unfolded(_))
+        ));
+    }
+}
+
+mod properties {
+    use arbitrary::{Arbitrary, Unstructured};
+
+    use crate::{Brex, arbitrary::LeagueName, decode, encode, encode::Error, encode_checked};
+
+    const CASES: u64 = 5000;
+
+    /// Run `property` over [`CASES`] deterministic pseudo-random buffers
+    fn check(mut property: impl FnMut(&mut Unstructured) -> arbitrary::Result<()>) {
+        for seed in 0..CASES {
+            // splitmix64
+            let mut state = seed;
+            let mut next = || {
+                state = state.wrapping_add(0x9e3779b97f4a7c15);
+                let mut z = state;
+                z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+                z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+                z ^ (z >> 31)
+            };
+            let len = (next() % 1024) as usize;
+            let data = (0..len).map(|_| next() as u8).collect::<Vec<_>>();
+            property(&mut Unstructured::new(&data)).unwrap();
+        }
+    }
+
+    #[test]
+    fn parse_display() {
+        check(|u| {
+            let brex = Brex::arbitrary(u)?;
+            let displayed = brex.to_string();
+            assert_eq!(Brex::parse(&displayed).unwrap(), brex, "{displayed:?}");
+            let pretty = format!("{brex:#}");
+            assert_eq!(Brex::parse(&pretty).unwrap(), brex, "{pretty:?}");
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn encode_league_names() {
+        check(|u| {
+            let LeagueName(name) = LeagueName::arbitrary(u)?;
+            let encoded = encode(&name).unwrap();
+            assert_eq!(decode(&encoded).unwrap(), name, "{encoded:?}");
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn encode_checked_alphabet() {
+        const CHARS: &[char] = &[
+            'a', 'b', '_', '.', '0', '1', '9', '❮', '❯', '{', '}', ',', '→',
+        ];
+        check(|u| {
+            let input = u
+                .arbitrary_iter::<u8>()?
+                .map(|ch| ch.map(|ch| CHARS[ch as usize % CHARS.len()]))
+                .collect::<arbitrary::Result<String>>()?;
+            match encode_checked(&input) {
+                Ok((encoded, _)) => assert_eq!(decode(&encoded).unwrap(), input, "{encoded:?}"),
+                Err(Error::Unrepresentable(_)) => {}
+                Err(err) => panic!("{input:?}: {err}"),
+            }
+            Ok(())
+        });
+    }
+}
+
+mod pattern {
+    use fst::{IntoStreamer as _, Set};
+    use regex::Regex;
+
+    use crate::{Brex, Numeric};
+
+    const PATTERN: &str =
+        "data/characters/ahri/❮skins/{root,skin{0→2,10→120}}animations/{skin{5}}❯.bin";
+
+    const MATCHING: &[&str] = &[
+        "data/characters/ahri/animations/skin5.bin",
+        "data/characters/ahri/skins/root.bin",
+        "data/characters/ahri/skins/skin0.bin",
+        "data/characters/ahri/skins/skin10.bin",
+        "data/characters/ahri/skins/skin109.bin",
+        "data/characters/ahri/skins/skin120.bin",
+        "data/characters/ahri/skins/skin2.bin",
+    ];
+    const NOT_MATCHING: &[&str] = &[
+        "data/characters/ahri/animations/skin0.bin",
+        "data/characters/ahri/skins/root0.bin",
+        "data/characters/ahri/skins/skin.bin",
+        "data/characters/ahri/skins/skin01.bin",
+        "data/characters/ahri/skins/skin121.bin",
+        "data/characters/ahri/skins/skin3.bin",
+        "data/characters/ahri/skins/skin9.bin",
+        "data/characters/ahri/skins/skin0.bin.bin",
+        "data/characters/ahri/skins/skin0_skins_skin1.bin",
+        "data/characters/aatrox/skins/skin0.bin",
+    ];
+
+    #[test]
+    fn matches() {
+        let brex = Brex::parse(PATTERN).unwrap();
+        let pattern = brex.compile();
+        for name in MATCHING {
+            assert!(pattern.matches(name), "{name}");
+        }
+        for name in NOT_MATCHING {
+            assert!(!pattern.matches(name), "{name}");
+        }
+    }
+
+    #[test]
+    fn fst_search() {
+        let brex = Brex::parse(PATTERN).unwrap();
+        let mut names = MATCHING.iter().chain(NOT_MATCHING).collect::<Vec<_>>();
+        names.sort();
+        let set = Set::from_iter(names).unwrap();
+
+        let found = set
+            .search(brex.compile())
+            .into_stream()
+            .into_strs()
+            .unwrap();
+        assert_eq!(found, MATCHING);
+    }
+
+    #[test]
+    fn candidates() {
+        let brex = Brex::parse(PATTERN).unwrap();
+        let pattern = brex.compile();
+        let candidates = pattern.candidates().collect::<Vec<_>>();
+        assert_eq!(candidates.len() as u64, pattern.candidate_count());
+        assert_eq!(candidates.len(), 1 + 3 + 111 + 1);
+        assert!(candidates.iter().all(|name| pattern.matches(name)));
+        for name in MATCHING {
+            assert!(candidates.contains(&name.to_string()), "{name}");
+        }
+
+        let plain = Brex::parse("data/characters/ahri/ahri.bin").unwrap();
+        assert_eq!(
+            plain.compile().candidates().collect::<Vec<_>>(),
+            ["data/characters/ahri/ahri.bin"]
+        );
+
+        let descending = Brex::parse("a❮_b{_c{5→2}}❯.bin").unwrap();
+        let descending = descending.compile();
+        assert_eq!(descending.candidates().count(), 0);
+        assert_eq!(descending.candidate_count(), 0);
+    }
+
+    #[test]
+    fn regex() {
+        let brex = Brex::parse(PATTERN).unwrap();
+        let regex = Regex::new(&brex.to_regex()).unwrap();
+        for name in MATCHING {
+            assert!(regex.is_match(name), "{name}");
+        }
+        for name in NOT_MATCHING {
+            assert!(!regex.is_match(name), "{name}");
+        }
+    }
+
+    #[test]
+    fn regex_numeric_ranges() {
+        for (start, end) in [
+            (0, 0),
+            (0, 9),
+            (7, 13),
+            (0, 120),
+            (95, 1005),
+            (1, 99999),
+            (4_294_967_200, u32::MAX),
+        ] {
+            let brex = Brex::builder()
+                .group("")
+                .numbers("n", [Numeric::Range(start, end)])
+                .build()
+                .unwrap();
+            let regex = Regex::new(&brex.to_regex()).unwrap();
+            let pattern = brex.compile();
+            let probes = (0..1100u64)
+                .chain([
+                    start as u64 - start.min(1) as u64,
+                    start as u64,
+                    end as u64,
+                    end as u64 + 1,
+                ])
+                .chain(u32::MAX as u64 - 100..=u32::MAX as u64 + 1);
+            for n in probes {
+                let name = format!("n{n}");
+                let expected = (start as u64..=end as u64).contains(&n);
+                assert_eq!(regex.is_match(&name), expected, "{start}→{end}: {name}");
+                assert_eq!(pattern.matches(&name), expected, "{start}→{end}: {name}");
+            }
+            assert!(!regex.is_match(&format!("n0{start}")));
+        }
+    }
+}
+
+mod stats {
+    use crate::{
+        Brex,
+        stats::{Len, Parity},
+    };
+
+    #[test]
+    fn stats() {
+        let input = "aatrox_skins_root_skins_skin0_skins_skin1_skins_skin2_skins_skin5.bin";
+        let brex = Brex::encode(input).unwrap();
+        let stats = brex.stats();
+        assert_eq!(brex.to_string(), "aatrox❮_skins{_root,_skin{0→2,5}}❯.bin");
+        assert_eq!(stats.input, Len::of(input));
+        assert_eq!(
+            stats.output,
+            Len {
+                chars: 38,
+                bytes: 44
+            }
+        );
+        assert_eq!((stats.groups, stats.suffixes), (1, 2));
+        assert_eq!((stats.ranges, stats.ranged_values), (1, 3));
+        assert_eq!((stats.preamble_parts, stats.parity), (1, Parity::Odd));
+        assert!(stats.char_ratio() < stats.byte_ratio());
+    }
+
+    #[test]
+    fn descending_ranges() {
+        let brex = Brex::parse("a❮_b{_c{5→3,7→8},_d{c→a}}❯").unwrap();
+        assert_eq!(brex.expand(), "a_b_c7_b_c8");
+        let stats = brex.stats();
+        assert_eq!((stats.ranges, stats.ranged_values), (3, 2));
+    }
+}
+
+mod batch {
+    use std::io::BufReader;
+
+    use crate::{
+        batch::{BatchOptions, encode_lines, encode_reader},
+        encode::Fallback,
+        encode_checked,
+    };
+
+    const INPUT: &str = include_str!("../cases/large_dump.in");
+
+    #[test]
+    fn order_preserved() {
+        let options = BatchOptions {
+            chunk_size: 100,
+            ..Default::default()
+        };
+        let encoded = encode_lines(INPUT.lines(), &options)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(encoded.len(), INPUT.lines().count());
+        for (line, encoded) in INPUT.lines().zip(encoded) {
+            assert_eq!(encoded.input, line.trim());
+            let (expected, fallback) = encode_checked(line.trim()).unwrap();
+            assert_eq!(encoded.encoded, expected);
+            assert_eq!(encoded.fallback, fallback);
+        }
+    }
+
+    #[test]
+    fn reader() {
+        let options = BatchOptions::default();
+        let from_reader = encode_reader(BufReader::new(INPUT.as_bytes()), &options)
+            .map(|encoded| encoded.unwrap().encoded)
+            .collect::<Vec<_>>();
+        let from_lines = encode_lines(INPUT.lines(), &options)
+            .map(|encoded| encoded.unwrap().encoded)
+            .collect::<Vec<_>>();
+        assert_eq!(from_reader, from_lines);
+    }
+
+    #[test]
+    fn comments() {
+        let options = BatchOptions {
+            comments: true,
+            ..Default::default()
+        };
+        let encoded = encode_lines(
+            ["# a", "", "x_skins_skin2_skins_skin1_skins_skin3"],
+            &options,
+        )
+        .map(|encoded| encoded.unwrap())
+        .map(|encoded| (encoded.comment, encoded.encoded, encoded.fallback))
+        .collect::<Vec<_>>();
+        assert_eq!(
+            encoded,
+            [
+                (true, String::new(), None),
+                (true, String::new(), None),
+                (
+                    false,
+                    "x❮_skins{_skin2,_skin1,_skin3}❯".to_string(),
+                    Some(Fallback::NoNumerics)
+                ),
+            ]
+        );
+    }
+}
+
+mod parser {
+    use super::nom_parser;
+    use crate::{
+        Brex, decode,
+        parse::{Error, Parser},
+    };
+
+    const TOKENS: &[&str] = &[
+        "a", "_b", ".bin", "0", "1", "12", "❮", "❯", "{", "}", ",", "→",
+    ];
+
+    /// Deterministic pseudo-random strings of brex-ish tokens
+    fn inputs() -> impl Iterator<Item = String> {
+        let mut state = 0u64;
+        let mut next = move || {
+            // splitmix64
+            state = state.wrapping_add(0x9e3779b97f4a7c15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+            z ^ (z >> 31)
+        };
+        (0..20000).map(move |_| {
+            let len = next() % 24;
+            (0..len)
+                .map(|_| TOKENS[next() as usize % TOKENS.len()])
+                .collect()
+        })
+    }
+
+    /// The nom parser panics on numbers that don't fit in a `u32`
+    fn fits(input: &str) -> bool {
+        input
+            .split(|ch: char| !ch.is_ascii_digit())
+            .all(|digits| digits.len() < 10)
+    }
+
+    #[test]
+    fn matches_nom() {
+        for input in inputs().filter(|input| fits(input)) {
+            assert_eq!(
+                Brex::parse(&input).unwrap(),
+                nom_parser::parse(&input).unwrap(),
+                "{input:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn decode_matches_expand() {
+        let mut parser = Parser::new();
+        let mut out = String::new();
+        for input in inputs().filter(|input| fits(input)) {
+            out.clear();
+            parser.decode_into(&input, &mut out).unwrap();
+            assert_eq!(out, Brex::parse(&input).unwrap().expand(), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn reused() {
+        let mut parser = Parser::new();
+        for line in include_str!("../cases/large_dump.in").lines() {
+            let encoded = crate::encode(line).unwrap();
+            assert_eq!(parser.decode(&encoded).unwrap(), line);
+            assert_eq!(parser.parse(&encoded), Brex::parse(&encoded));
+        }
+    }
+
+    #[test]
+    fn malformed_block_is_postamble() {
+        let brex = Brex::parse("a❮_b{_c,}❯.bin").unwrap();
+        assert_eq!(brex.preamble, Some("a"));
+        assert!(brex.groups.is_empty());
+        assert_eq!(brex.postamble, Some("❮_b{_c,}❯.bin"));
+    }
+
+    #[test]
+    fn number_overflow() {
+        assert_eq!(
+            decode("a❮_b{_c{1,99999999999}}❯"),
+            Err(Error::NumberOverflow { position: 12 })
+        );
+        // only reported if the block is otherwise well formed
+        assert_eq!(
+            decode("a❮_b{_c{99999999999"),
+            Ok("a❮_b{_c{99999999999".to_string())
+        );
+    }
+
+    #[test]
+    fn strict() {
+        let malformed = |input| match Brex::parse_strict(input) {
+            Err(Error::Malformed { position }) => input[position..].chars().next(),
+            other => panic!("{input:?} parsed strictly as {other:?}"),
+        };
+        assert_eq!(malformed("a❮_b{_c,}❯.bin"), Some('}'));
+        assert_eq!(malformed("a❮_b{_c{1,x→2}}❯"), Some('→'));
+        assert_eq!(malformed("a❮_b{_c}"), None);
+        assert_eq!(malformed("a_b{_c}❯"), Some('❯'));
+        assert!(Brex::parse_strict("a❮ _b {_c, _d{1→3}} ❯.bin").is_ok());
+        assert!(Brex::parse_strict("plain.bin").is_ok());
+    }
+}
+
+mod pretty {
+    use crate::{Brex, decode, encode};
+
+    const PRETTY: &str = "\
+aatrox❮
+    _base {_a, _b}
+    _skins{_root, _skin{0→2,5}}
+❯.bin";
+
+    #[test]
+    fn display() {
+        let brex = Brex::parse("aatrox❮_base{_a,_b}_skins{_root,_skin{0→2,5}}❯.bin").unwrap();
+        assert_eq!(format!("{brex:#}"), PRETTY);
+    }
+
+    #[test]
+    fn parse_whitespace_insensitive() {
+        let compact = Brex::parse("aatrox❮_base{_a,_b}_skins{_root,_skin{0→2,5}}❯.bin").unwrap();
+        assert_eq!(Brex::parse(PRETTY).unwrap(), compact);
+        let spaced =
+            "aatrox❮\n\t_base { _a ,\n _b }\n  _skins{ _root , _skin { 0 → 2 , 5 } }\n❯.bin";
+        assert_eq!(Brex::parse(spaced).unwrap(), compact);
+    }
+
+    #[test]
+    fn roundtrip_cases() {
+        for line in include_str!("../cases/large_dump.in").lines() {
+            let encoded = encode(line).unwrap();
+            let brex = Brex::parse(&encoded).unwrap();
+            assert_eq!(decode(&format!("{brex:#}")).unwrap(), line);
+        }
+    }
+}
+
+mod diff {
+    use crate::{
+        Brex, Numeric,
+        diff::{Changed, Side, SuffixDiff},
+    };
+
+    #[test]
+    fn added_skins() {
+        let old = Brex::parse("aatrox❮_skins{_root,_skin{0→29}}❯.bin").unwrap();
+        let new = Brex::parse("aatrox❮_skins{_root,_skin{0→32}}❯.bin").unwrap();
+        let diff = old.diff(&new);
+        assert_eq!(diff.groups.len(), 1);
+        assert_eq!(
+            diff.groups[0].suffixes,
+            [SuffixDiff {
+                side: Side::Added,
+                suffix: "_skin",
+                numerics: Some(vec![Numeric::Range(30, 32)]),
+                letters: None,
+            }]
+        );
+        assert_eq!(diff.to_string(), "_skins{+_skin{30→32}}\n");
+        assert!(new.diff(&new).is_empty());
+    }
+
+    #[test]
+    fn groups_suffixes_and_ambles() {
+        let old = Brex::parse("a❮_base{_x,_y}_skins{_root,_skin{0→5,9}}_old{_z}❯.bin").unwrap();
+        let new =
+            Brex::parse("b❮_base{_y,_x}_new{_z}_skins{_skin{0,2→5,7→8},_chroma}❯.wad").unwrap();
+        let diff = old.diff(&new);
+        assert_eq!(
+            diff.preamble,
+            Some(Changed {
+                from: Some("a"),
+                to: Some("b")
+            })
+        );
+        assert_eq!(
+            diff.to_string(),
+            "\
+preamble: \"a\" → \"b\"
++_new{_z}
+-_old{_z}
+_skins{-_root,-_skin{1,9},+_chroma,+_skin{7→8}}
+postamble: \".bin\" → \".wad\"
+"
+        );
+    }
+
+    #[test]
+    fn same_components() {
+        let old = Brex::parse("a❮_b{_c{0→2},_d}❯").unwrap();
+        let new = Brex::parse("a❮_b{_c{2,0,1},_d}❯").unwrap();
+        assert!(old.diff(&new).is_empty());
+        assert_eq!(old.expand(), new.expand());
+
+        // suffix order isn't compared, though it changes the expansion
+        let reordered = Brex::parse("a❮_b{_d,_c{2,0,1}}❯").unwrap();
+        assert!(old.diff(&reordered).is_empty());
+        assert_ne!(old.expand(), reordered.expand());
+    }
+}
+
+mod delta {
+    use test_each_file::test_each_file;
+
+    use crate::delta::{DeltaDecoder, DeltaEncoder, DeltaOptions};
+
+    test_each_file!( in "./brex/cases/" as cases => roundtrip);
+
+    fn roundtrip(input: &str) {
+        let options = DeltaOptions::default();
+        let mut encoder = DeltaEncoder::new(options.clone());
+        let mut decoder = DeltaDecoder::new(options.window);
+        for line in input.lines() {
+            let encoded = encoder.encode(line).unwrap();
+            assert_eq!(decoder.decode(&encoded).unwrap(), line, "{encoded:?}");
+        }
+    }
+
+    #[test]
+    fn smaller_than_per_line() {
+        let input = include_str!("../cases/large_dump.in");
+        let mut encoder = DeltaEncoder::default();
+        let delta = input
+            .lines()
+            .map(|line| encoder.encode(line).unwrap().chars().count())
+            .sum::<usize>();
+        let per_line = input
+            .lines()
+            .map(|line| crate::encode(line).unwrap().chars().count())
+            .sum::<usize>();
+        assert!(delta * 4 < per_line * 3, "{delta} vs {per_line}");
+    }
+
+    #[test]
+    fn deltas() {
+        let lines = [
+            "aatrox_skins_root_skins_skin0_skins_skin1_skins_skin2_skins_skin3.bin",
+            "aatrox_skins_root_skins_skin0_skins_skin1_skins_skin2.bin",
+            "aatrox_skins_root_skins_skin0_skins_skin1_skins_skin2.bin",
+            "aatrox_skins_root_skins_skin0_skins_skin1_skins_skin2_skins_skin3.bin",
+            "Δ_a_b",
+        ];
+        let mut encoder = DeltaEncoder::default();
+        let encoded = lines
+            .iter()
+            .map(|line| encoder.encode(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            encoded,
+            [
+                "aatrox❮_skins{_root,_skin{0→3}}❯.bin",
+                "Δ❮~_skins{-_skin{3}}❯",
+                "Δ❮❯",
+                "Δ3❮❯",
+                "Δ0Δ_a_b",
+            ]
+        );
+        let mut decoder = DeltaDecoder::default();
+        for (line, encoded) in lines.iter().zip(&encoded) {
+            assert_eq!(&decoder.decode(encoded).unwrap(), line);
+        }
+    }
+}
+
+mod document {
+    use test_each_file::test_each_file;
+
+    use crate::{
+        document::{self, BrexDocument, Decoder, Delimiter, Dialect, Entry, Error, Header, Reader},
+        encode::{EncodeOptions, Fallback},
+    };
+
+    test_each_file!( in "./brex/cases/" as cases => roundtrip);
+
+    fn roundtrip(input: &str) {
+        for dialect in [Dialect::Plain, Dialect::Delta] {
+            for delimiter in [Delimiter::Line, Delimiter::Blank] {
+                let header = Header {
+                    dialect,
+                    delimiter,
+                    ..Default::default()
+                };
+                let document =
+                    BrexDocument::from_plaintext(input, header, &EncodeOptions::default()).unwrap();
+                let written = document.to_string();
+                let read = BrexDocument::parse(&written).unwrap();
+                assert_eq!(read.header, document.header);
+                assert_eq!(
+                    read.to_plaintext().unwrap(),
+                    document.to_plaintext().unwrap()
+                );
+                let expected = input
+                    .lines()
+                    .filter(|line| !line.trim().is_empty())
+                    .map(|line| format!("{line}\n"))
+                    .collect::<String>();
+                assert_eq!(read.to_plaintext().unwrap(), expected);
+            }
+        }
+    }
+
+    const DOCUMENT: &str = "\
+#!brex version=1 dialect=plain delimiter=line
+# skins
+@patch=14.1
+@kind=bin
+aatrox❮_skins{_root,_skin{0→2}}❯.bin
+\\#hash
+# trailing
+";
+
+    #[test]
+    fn parse() {
+        let document = BrexDocument::parse(DOCUMENT).unwrap();
+        assert_eq!(document.header, Header::default());
+        assert_eq!(
+            document.entries,
+            [
+                Entry {
+                    comments: vec![" skins".into()],
+                    metadata: vec![
+                        ("patch".into(), "14.1".into()),
+                        ("kind".into(), "bin".into())
+                    ],
+                    brex: "aatrox❮_skins{_root,_skin{0→2}}❯.bin".into(),
+                },
+                Entry {
+                    brex: "#hash".into(),
+                    ..Default::default()
+                }
+            ]
+        );
+        assert_eq!(document.comments, [" trailing"]);
+        assert_eq!(
+            document.decode().unwrap(),
+            [
+                "aatrox_skins_root_skins_skin0_skins_skin1_skins_skin2.bin",
+                "#hash"
+            ]
+        );
+        assert_eq!(document.to_string(), DOCUMENT);
+    }
+
+    #[test]
+    fn reader() {
+        let document = BrexDocument::parse(DOCUMENT).unwrap();
+        let mut reader = Reader::new(Header {
+            dialect: Dialect::Delta,
+            ..Default::default()
+        });
+        let mut decoder = None;
+        let mut entries = Vec::new();
+        let mut names = Vec::new();
+        for line in DOCUMENT.lines() {
+            if let Some(entry) = reader.read_line(line).unwrap() {
+                let decoder = decoder.get_or_insert_with(|| Decoder::new(reader.header().dialect));
+                names.push(decoder.decode(&entry).unwrap());
+                entries.push(entry);
+            }
+        }
+        assert_eq!(reader.header(), &document.header);
+        // The trailing comment completes the last entry
+        let (last, comments) = reader.finish().unwrap();
+        assert_eq!(last, None);
+        assert_eq!(entries, document.entries);
+        assert_eq!(comments, document.comments);
+        assert_eq!(names, document.decode().unwrap());
+
+        // Without a header, the reader's header is kept
+        let mut reader = Reader::new(Header {
+            dialect: Dialect::Delta,
+            ..Default::default()
+        });
+        assert_eq!(reader.read_line("a❮_b{0,1}❯").unwrap(), None);
+        assert_eq!(reader.header().dialect, Dialect::Delta);
+        let mut reader = Reader::new(Header::default());
+        reader.read_line("@key=value").unwrap();
+        assert!(matches!(reader.finish(), Err(Error::DanglingMetadata(1))));
+    }
+
+    #[test]
+    fn fallbacks() {
+        let input =
+            "# a\naatrox_skins_skin0_skins_skin1.bin\nx_skins_skin2_skins_skin1_skins_skin3\n";
+        let (document, fallbacks) = BrexDocument::from_plaintext_checked(
+            input,
+            Header::default(),
+            &EncodeOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(document.entries.len(), 2);
+        assert_eq!(
+            fallbacks,
+            [
+                ("aatrox_skins_skin0_skins_skin1.bin", None),
+                (
+                    "x_skins_skin2_skins_skin1_skins_skin3",
+                    Some(Fallback::NoNumerics)
+                )
+            ]
+        );
+    }
+
+    #[test]
+    fn escape() {
+        for (entry, escaped) in [
+            ("a❮_b{_c,_d}❯", "a❮_b{_c,_d}❯"),
+            ("#hash", "\\#hash"),
+            ("@key=value", "\\@key=value"),
+            ("\\a", "\\\\a"),
+        ] {
+            assert_eq!(document::escape(entry), escaped);
+        }
+    }
+
+    #[test]
+    fn pretty_entries() {
+        let header = Header {
+            delimiter: Delimiter::Blank,
+            ..Default::default()
+        };
+        let document = BrexDocument::from_plaintext(
+            "a_skins_root_skins_skin0_skins_skin1.bin\ne_skins_root_skins_skin2_skins_skin3.bin\n",
+            header,
+            &EncodeOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            document.to_string(),
+            "\
+#!brex version=1 dialect=plain delimiter=blank
+a❮
+    _skins{_root, _skin{0→1}}
+❯.bin
+
+e❮
+    _skins{_root, _skin{2→3}}
+❯.bin
+"
+        );
+    }
+
+    #[test]
+    fn headerless() {
+        let document = BrexDocument::parse("# comment\na❮_b{_c,_d}❯\n").unwrap();
+        assert_eq!(document.header, Header::default());
+        assert_eq!(document.decode().unwrap(), ["a_b_c_b_d"]);
+    }
+
+    #[test]
+    fn errors() {
+        assert!(matches!(
+            BrexDocument::parse("#!brex version=2"),
+            Err(Error::UnsupportedVersion(2))
+        ));
+        assert!(matches!(
+            BrexDocument::parse("#!brex dialect=fancy"),
+            Err(Error::InvalidHeader(_))
+        ));
+        assert!(matches!(
+            BrexDocument::parse("a\n@key"),
+            Err(Error::InvalidMetadata(2))
+        ));
+        assert!(matches!(
+            BrexDocument::parse("a\n@key=value\n"),
+            Err(Error::DanglingMetadata(2))
+        ));
+    }
+}
+
+mod letters {
+    use crate::{
+        Alphabetic, Brex, Numeric, Suffix,
+        delta::{DeltaDecoder, DeltaEncoder},
+        encode::EncodeOptions,
+        parse,
+    };
+
+    #[test]
+    fn encodes_runs() {
+        let input = "aatrox_chromas_a_chromas_b_chromas_c_chromas_d.bin";
+        assert_eq!(
+            crate::encode(input).unwrap(),
+            "aatrox❮_chromas{_{a→d}}❯.bin"
+        );
+        let options = EncodeOptions {
+            letters: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            crate::encode_with(input, &options).unwrap(),
+            "aatrox❮_chromas{_a,_b,_c,_d}❯.bin"
+        );
+        // string order restarts at `aa`, so it can't share a range with `z`
+        assert_eq!(
+            crate::encode("kaisa_tiers_y_tiers_z_tiers_aa_tiers_ab_tiers_ac.bin").unwrap(),
+            "kaisa❮_tiers{_y,_z,_a{a→c}}❯.bin"
+        );
+    }
+
+    #[test]
+    fn parses_mixed_blocks() {
+        let brex = Brex::parse("a❮_x{_v{0→1,a→b},_{y→ab}}❯").unwrap();
+        assert_eq!(
+            brex.groups[0].suffixes,
+            [
+                Suffix {
+                    suffix: "_v",
+                    numerics: Some(vec![Numeric::Range(0, 1)]),
+                    letters: Some(vec![Alphabetic::Range(0, 1)]),
+                },
+                Suffix::alphabetic("_", vec![Alphabetic::Range(24, 27)]),
+            ]
+        );
+        let expanded = "a_x_v0_x_v1_x_va_x_vb_x_aa_x_ab_x_y_x_z";
+        assert_eq!(brex.expand(), expanded);
+        assert_eq!(crate::decode(&brex.to_string()).unwrap(), expanded);
+        assert_eq!(Brex::parse(&format!("{brex:#}")).unwrap(), brex);
+
+        // ranges between numbers & letters are not a numeric block
+        assert_eq!(Brex::parse("a❮_x{_v{0→b}}❯").unwrap().groups, []);
+        assert_eq!(
+            Brex::parse("a❮_x{_v{aaaaaaaa}}❯"),
+            Err(parse::Error::LettersOverflow { position: 10 })
+        );
+    }
+
+    #[test]
+    fn indexes() {
+        for (letters, index) in [("a", 0), ("z", 25), ("aa", 26), ("zz", 701), ("aaa", 702)] {
+            assert_eq!(Alphabetic::index(letters), Some(index));
+            assert_eq!(Alphabetic::Single(index).to_string(), letters);
+        }
+        assert_eq!(Alphabetic::Single(u32::MAX).to_string(), "mwlqkwv");
+        assert_eq!(Alphabetic::index("mwlqkwv"), Some(u32::MAX));
+        assert_eq!(Alphabetic::index("mwlqkww"), None);
+        assert_eq!(Alphabetic::index(""), None);
+    }
+
+    #[test]
+    fn patterns() {
+        let brex = Brex::parse("a❮_x{_v{2,a→c,y→ab}}❯.bin").unwrap();
+        let pattern = brex.compile();
+        let regex = regex::Regex::new(&brex.to_regex()).unwrap();
+        for name in [
+            "a_x_v2.bin",
+            "a_x_va.bin",
+            "a_x_vc.bin",
+            "a_x_vz.bin",
+            "a_x_vab.bin",
+        ] {
+            assert!(pattern.matches(name), "{name}");
+            assert!(regex.is_match(name), "{name}");
+        }
+        for name in [
+            "a_x_vd.bin",
+            "a_x_vx.bin",
+            "a_x_vac.bin",
+            "a_x_v.bin",
+            "a_x_va2.bin",
+        ] {
+            assert!(!pattern.matches(name), "{name}");
+            assert!(!regex.is_match(name), "{name}");
+        }
+    }
+
+    #[test]
+    fn diffs_and_deltas() {
+        let old = Brex::parse("a❮_x{_{a→c}}❯").unwrap();
+        let new = Brex::parse("a❮_x{_{a,b,c,d,e}}❯").unwrap();
+        assert_eq!(old.diff(&new).to_string(), "_x{+_{d→e}}\n");
+
+        let lines = [
+            "aatrox_chromas_a_chromas_b_chromas_c_chromas_d.bin",
+            "aatrox_chromas_a_chromas_b_chromas_c_chromas_d_chromas_e.bin",
+        ];
+        let mut encoder = DeltaEncoder::default();
+        let mut decoder = DeltaDecoder::default();
+        let encoded = lines.map(|line| encoder.encode(line).unwrap());
+        assert_eq!(encoded[1], "Δ❮~_chromas{+_{e}}❯");
+        for (line, encoded) in lines.iter().zip(&encoded) {
+            assert_eq!(&decoder.decode(encoded).unwrap(), line);
+        }
+    }
+}
+
+mod bytes {
+    use crate::{
+        Brex, Numeric,
+        bytes::{self, Suffix},
+        encode::{Case, EncodeOptions},
+        util::split_inclusive_start,
+    };
+
+    #[test]
+    fn matches_str() {
+        let cases = [
+            include_str!("../cases/edge_cases.in"),
+            include_str!("../cases/letters.in"),
+            include_str!("../cases/substring_order.in"),
+        ];
+        for line in cases.iter().flat_map(|case| case.lines()) {
+            let encoded = crate::encode(line).unwrap();
+            assert_eq!(bytes::encode(line.as_bytes()).unwrap(), encoded.as_bytes());
+            assert_eq!(bytes::decode(encoded.as_bytes()).unwrap(), line.as_bytes());
+            let brex = Brex::parse(&encoded).unwrap();
+            let parsed = bytes::parse(encoded.as_bytes()).unwrap();
+            assert_eq!(parsed.to_text(), brex.to_string().as_bytes());
+            assert_eq!(parsed.expand(), brex.expand().as_bytes());
+        }
+    }
+
+    #[test]
+    fn non_utf8() {
+        let input = b"a_skin_\xfe \xa0_skin_\xff1_skin_\xff2_skin_\xff3.bin";
+        let encoded = bytes::encode(input).unwrap();
+        assert_eq!(
+            encoded,
+            [
+                &b"a"[..],
+                "❮_skin{".as_bytes(),
+                b"_\xfe \xa0,_\xff",
+                "{1→3}}❯.bin".as_bytes()
+            ]
+            .concat()
+        );
+        assert_eq!(bytes::decode(&encoded).unwrap(), input);
+
+        let brex = bytes::parse(&encoded).unwrap();
+        assert_eq!(
+            brex.groups[0].suffixes,
+            [
+                Suffix::simple(b"_\xfe \xa0"),
+                Suffix::numeric(b"_\xff", vec![Numeric::Range(1, 3)])
+            ]
+        );
+        assert_eq!(brex.expand(), input);
+
+        let options = EncodeOptions {
+            case: Case::Lower,
+            ..Default::default()
+        };
+        let (encoded, fallback) =
+            bytes::encode_checked_with(b"A_X_\xc01_X_\xc02", &options).unwrap();
+        assert_eq!(fallback, None);
+        assert_eq!(bytes::decode(&encoded).unwrap(), b"a_x_\xc01_x_\xc02");
+
+        let parts = split_inclusive_start(&b"\xff_a_\xfe"[..], '_').collect::<Vec<_>>();
+        assert_eq!(parts, [&b"\xff"[..], b"_a", b"_\xfe"]);
+    }
+}
+
+mod binary {
+    use crate::{Alphabetic, Brex, Numeric, Suffix, binary::Error};
+
+    #[test]
+    fn roundtrips() {
+        let cases = [
+            include_str!("../cases/edge_cases.in"),
+            include_str!("../cases/large_dump.in"),
+            include_str!("../cases/letters.in"),
+        ];
+        let (mut text_len, mut binary_len) = (0, 0);
+        for line in cases.iter().flat_map(|case| case.lines()) {
+            let encoded = crate::encode(line).unwrap();
+            let brex = Brex::parse(&encoded).unwrap();
+            let bytes = brex.to_bytes();
+            let decoded = Brex::from_bytes(&bytes).unwrap();
+            assert_eq!(decoded, brex);
+            assert_eq!(decoded.to_string(), encoded);
+            text_len += encoded.len();
+            binary_len += bytes.len();
+        }
+        assert!(binary_len < text_len, "{binary_len} >= {text_len}");
+    }
+
+    #[test]
+    fn layout() {
+        let brex = Brex {
+            preamble: Some("a"),
+            groups: vec![crate::Group {
+                prefix: "_x",
+                suffixes: vec![
+                    Suffix::simple("a"),
+                    Suffix::numeric("_x", vec![Numeric::Single(3), Numeric::Range(200, 5)]),
+                    Suffix::alphabetic("_", vec![Alphabetic::Range(0, 2)]),
+                ],
+            }],
+            postamble: None,
+        };
+        let bytes = brex.to_bytes();
+        #[rustfmt::skip]
+        assert_eq!(bytes, [
+            b'b', b'r', b'e', b'x', 1,
+            3, 1, b'a', 2, b'_', b'x', 1, b'_',
+            1, 0, 1,
+            1, 3,
+            0, 0,
+            1, 1, 2, 6, 0x91, 0x03, 0x85, 0x03,
+            2, 2, 1, 1, 4,
+        ]);
+        assert_eq!(Brex::from_bytes(&bytes).unwrap(), brex);
+
+        for len in 0..bytes.len() {
+            assert_eq!(Brex::from_bytes(&bytes[..len]), Err(Error::UnexpectedEof));
+        }
+        let mut newer = bytes.clone();
+        newer[4] = 2;
+        assert_eq!(Brex::from_bytes(&newer), Err(Error::UnsupportedVersion(2)));
+        assert_eq!(Brex::from_bytes(b"xerb\x01"), Err(Error::InvalidMagic));
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert_eq!(Brex::from_bytes(&trailing), Err(Error::TrailingBytes(1)));
+    }
+}
+
+mod paths {
+    use crate::{Brex, encode::Error, paths::DIRECTORY};
+
+    /// Every path of a few champions' skin folders, sorted
+    fn tree() -> Vec<String> {
+        let mut paths = Vec::new();
+        for champion in ["ahri", "annie", "zed"] {
+            for skin in 0..12 {
+                for file in ["base.dds", "base.skl", "base.skn", "particles.bin"] {
+                    paths.push(format!(
+                        "assets/characters/{champion}/skins/skin{skin}/{file}"
+                    ));
+                }
+            }
+            paths.push(format!("data/characters/{champion}/{champion}.bin"));
+        }
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn roundtrips() {
+        let paths = tree();
+        let encoded = Brex::encode_many(&paths).unwrap();
+        assert_eq!(Brex::expand_many(&encoded).unwrap(), paths);
+        assert_eq!(
+            encoded[0],
+            "❮\
+                |assets/characters/ahri/skins{/skin{0→11}}\
+                |assets/characters/annie/skins{/skin{0→11}}\
+                |assets/characters/zed/skins{/skin{0→11}}\
+                ~/base{.dds,.skl,.skn}\
+            ❯~/particles.bin"
+        );
+        // then a string for each champion's data folder
+        assert_eq!(encoded.len(), 4);
+    }
+
+    #[test]
+    fn unsorted() {
+        let paths = [
+            "b/skin2/a.bin",
+            "b/skin10/a.bin",
+            "a.bin",
+            "b/skin1/a.bin",
+            "b/skin1/a.bin",
+            "",
+        ];
+        let encoded = Brex::encode_many(&paths).unwrap();
+        let mut sorted = paths;
+        sorted.sort();
+        assert_eq!(Brex::expand_many(&encoded).unwrap(), sorted);
+    }
+
+    #[test]
+    fn uneven() {
+        let paths = [
+            "a/skin0/base.dds",
+            "a/skin0/base.skn",
+            "a/skin1/base.dds",
+            "a/skin1/base.skn",
+            "a/skin1/extra.bin",
+            "a/skin2/base.dds",
+            "a/skin2/base.skn",
+            "a/skin2/extra.bin",
+            "a/skin3/base.dds",
+            "a/skin3/base.skn",
+        ];
+        let encoded = Brex::encode_many(&paths).unwrap();
+        assert_eq!(Brex::expand_many(&encoded).unwrap(), paths);
+        // the shared files are encoded once for every directory, and the extra files separately
+        assert_eq!(
+            encoded,
+            [
+                "❮|a{/skin{0→3}}~/base{.dds,.skn}❯",
+                "|a/skin1|a/skin2~/extra.bin"
+            ]
+        );
+    }
+
+    #[test]
+    fn reserved() {
+        assert!(matches!(
+            Brex::encode_many(&[format!("a{DIRECTORY}b.bin")]),
+            Err(Error::Unrepresentable(_))
+        ));
+        assert_eq!(
+            Brex::encode_many::<&str>(&[]).unwrap(),
+            Vec::<String>::new()
+        );
+    }
+}
@@ -1,25 +1,59 @@
-/// Sort function that has substrings > their superstring's
-/// (this is the opposite of normal str::cmp behaviour)
-///
-/// e.g "superfan" > "superfanvariant"
-pub fn inverted_substr_sort(a: &str, b: &str) -> std::cmp::Ordering {
-    let len = a.len().min(b.len());
-    match &a[..len].cmp(&b[..len]) {
-        std::cmp::Ordering::Equal => b.len().cmp(&a.len()),
-        order => *order,
+use crate::Text;
+
+/// Write the decimal digits of `n` into the end of `buf`, returning them
+pub fn decimal(mut n: u32, buf: &mut [u8; 10]) -> &[u8] {
+    let mut i = buf.len();
+    loop {
+        i -= 1;
+        buf[i] = b'0' + (n % 10) as u8;
+        n /= 10;
+        if n == 0 {
+            return &buf[i..];
+        }
     }
 }
 
+/// Write the letter sequence at `index` (`a`, `b`, …, `z`, `aa`, …) into the end of `buf`, returning it
+pub fn letters(index: u32, buf: &mut [u8; 7]) -> &str {
+    let mut n = u64::from(index) + 1;
+    let mut i = buf.len();
+    while n > 0 {
+        n -= 1;
+        i -= 1;
+        buf[i] = b'a' + (n % 26) as u8;
+        n /= 26;
+    }
+    // only ever ascii letters
+    core::str::from_utf8(&buf[i..]).unwrap_or_default()
+}
+
+/// Adapts a byte buffer to [`core::fmt::Write`]
+pub struct ByteWriter<'a>(pub &'a mut alloc::vec::Vec<u8>);
+
+impl core::fmt::Write for ByteWriter<'_> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.0.extend_from_slice(s.as_bytes());
+        Ok(())
+    }
+}
+
+/// Find `needle` in `haystack`, returning its byte position
+pub fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
 #[derive(Clone, Debug)]
 /// An iterator over substrings separated by a given deliminator character. The deliminator is included at the start of each substring
 /// This struct is created by the [`split_inclusive_start`] function.
-pub struct SplitInclusiveStart<'a> {
-    remainder: &'a str,
+pub struct SplitInclusiveStart<'a, T: ?Sized> {
+    remainder: &'a T,
     delim: char,
 }
 
-impl<'a> SplitInclusiveStart<'a> {
-    pub fn new(s: &'a str, delim: char) -> Self {
+impl<'a, T: Text + ?Sized> SplitInclusiveStart<'a, T> {
+    pub fn new(s: &'a T, delim: char) -> Self {
         Self {
             remainder: s,
             delim,
@@ -27,39 +61,40 @@ impl<'a> SplitInclusiveStart<'a> {
     }
 }
 
-impl<'a> Iterator for SplitInclusiveStart<'a> {
-    type Item = &'a str;
+impl<'a, T: Text + ?Sized> Iterator for SplitInclusiveStart<'a, T> {
+    type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.remainder.is_empty() {
+        let len = self.remainder.as_bytes().len();
+        if len == 0 {
             return None;
         }
 
-        //eprintln!("remainder: {}", self.remainder);
-        if let Some(pos) = &self.remainder[self.delim.len_utf8()..].find(self.delim) {
-            let pos = *pos + 1;
-            //eprintln!("pos: {pos}");
-            if pos == 0 {
-                // remainder starts with delimiter
-                let len = self.delim.len_utf8();
-                let (piece, rest) = self.remainder.split_at(len);
-                self.remainder = rest;
-                Some(piece)
-            } else {
-                let (piece, rest) = self.remainder.split_at(pos);
-                self.remainder = rest; // rest starts with delimiter
-                Some(piece)
-            }
+        // the first char is part of this piece, whether or not it's a delimiter
+        let first = self.remainder.first_len();
+        let mut delim = [0; 4];
+        let delim = self.delim.encode_utf8(&mut delim).as_bytes();
+        // a whole encoded char can only match on char boundaries
+        let split = find_bytes(&self.remainder.as_bytes()[first..], delim).and_then(|pos| {
+            let (piece, rest) = (0..first + pos, first + pos..len);
+            Some((self.remainder.slice(piece)?, self.remainder.slice(rest)?))
+        });
+        if let Some((piece, rest)) = split {
+            self.remainder = rest; // rest starts with delimiter
+            Some(piece)
         } else {
             // no more delimiters
             let piece = self.remainder;
-            self.remainder = "";
+            self.remainder = T::empty();
             Some(piece)
         }
     }
 }
 
 /// Return an iterator over substrings separated by a given deliminator character. The deliminator is included at the start of each substring
-pub fn split_inclusive_start<'a>(s: &'a str, delim: char) -> SplitInclusiveStart<'a> {
+pub fn split_inclusive_start<'a, T: Text + ?Sized>(
+    s: &'a T,
+    delim: char,
+) -> SplitInclusiveStart<'a, T> {
     SplitInclusiveStart::new(s, delim)
 }
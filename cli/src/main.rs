@@ -1,15 +1,24 @@
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, HashSet},
+    fmt::Display,
     fs::File,
-    io::{BufRead as _, BufReader, BufWriter, Write, stdout},
-    path::PathBuf,
+    io::{BufRead as _, BufReader, BufWriter, Read as _, Write, stdout},
+    path::{Path, PathBuf},
 };
 
+use brex::{
+    Brex,
+    batch::BatchOptions,
+    document::{self, BrexDocument, Delimiter, Dialect, Header},
+    encode::{Case, EncodeOptions, Length},
+};
 use clap::{Parser, Subcommand};
 use clap_stdin::FileOrStdin;
 use miette::IntoDiagnostic;
 
-use poro_hash::{BinHash, FromStrRadix as _, Hashtable, WadHash, fst};
+use poro_hash::{
+    BinHash, FromStrRadix as _, Hash, Hashtable, WadHash, fst, guess::guess, linked::LinkedBins,
+};
 
 #[derive(Parser)]
 pub struct Cli {
@@ -31,11 +40,36 @@ pub enum HashCommand {
         kind: HashKind,
         input: PathBuf,
         output: PathBuf,
+        /// Format to write. The size of both formats is reported.
+        #[arg(long, value_enum, default_value_t = TableFormat::Fst)]
+        format: TableFormat,
     },
     Decompress {
         kind: HashKind,
         input: PathBuf,
         output: PathBuf,
+        /// Format to read. The size of both formats is reported.
+        #[arg(long, value_enum, default_value_t = TableFormat::Fst)]
+        format: TableFormat,
+    },
+    /// Find the candidates of a brex template whose hashes are in a file of unknown hashes
+    Guess {
+        /// Brex pattern of the candidate paths, e.g `data/characters/ahri/❮skins/{root,skin{0→120}}❯.bin`
+        #[arg(long)]
+        template: String,
+        /// File of unknown hex hashes, one per line. Anything after the hash is ignored.
+        #[arg(long)]
+        unknown: PathBuf,
+        #[arg(long, value_enum, default_value_t = HashKind::Wad)]
+        kind: HashKind,
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Write the paths & hashes of the bins in linked bin names (plaintext or brex), one name per line
+    Linked {
+        input: FileOrStdin,
+        #[arg(short, long)]
+        output: Option<PathBuf>,
     },
 }
 
@@ -45,20 +79,109 @@ pub enum HashKind {
     Wad,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug, clap::ValueEnum)]
+pub enum TableFormat {
+    /// A raw `fst::Set` of paths
+    Fst,
+    /// Paths grouped by directory, with brex encoded file names
+    Brex,
+}
+
 #[derive(Subcommand, Clone)]
 pub enum BrexCommand {
     Encode {
         input: FileOrStdin,
         #[arg(short, long)]
         output: Option<PathBuf>,
+        /// Skip verifying that each encoded line decodes back to its input
+        #[arg(long, conflicts_with_all = ["pretty", "delta"])]
+        unchecked: bool,
+        /// Print a tree of how each line was encoded, instead of writing a brex document
+        #[arg(long)]
+        explain: bool,
+        /// Write a document of pretty printed entries, separated by blank lines
+        #[arg(long, conflicts_with = "explain")]
+        pretty: bool,
+        /// Write a delta document, encoding lines as deltas against previous lines where shorter
+        #[arg(long, conflicts_with_all = ["explain", "pretty"])]
+        delta: bool,
+        /// Don't echo each input line to stderr
+        #[arg(short, long)]
+        quiet: bool,
+        /// Number of lines to read ahead & encode in parallel at once
+        #[arg(long, default_value_t = 4096)]
+        chunk_size: usize,
+        #[command(flatten)]
+        options: EncodeArgs,
     },
+    /// Structurally compare two brex strings
+    Diff { old: String, new: String },
     Decode {
         input: FileOrStdin,
         #[arg(short, long)]
         output: Option<PathBuf>,
+        /// Don't echo each input line to stderr
+        #[arg(short, long)]
+        quiet: bool,
+        /// Decode a headerless document as deltas, as written by `encode --delta`
+        #[arg(long)]
+        delta: bool,
     },
 }
 
+#[derive(clap::Args, Clone)]
+pub struct EncodeArgs {
+    /// Minimum number of members a group needs to be factored out
+    #[arg(long, default_value_t = 2)]
+    min_group_size: usize,
+    /// Don't collapse numeric suffixes into ranges
+    #[arg(long)]
+    no_numerics: bool,
+    /// Don't collapse consecutive letter suffixes into ranges
+    #[arg(long)]
+    no_letters: bool,
+    /// Number of trailing `.`-separated segments to keep as postamble
+    #[arg(long, default_value_t = 1)]
+    postamble_segments: usize,
+    /// Which length to minimise
+    #[arg(long, value_enum, default_value_t = LengthKind::Chars)]
+    optimise_for: LengthKind,
+    /// How letter case is treated. `lower` groups parts case-insensitively, emitting lower case.
+    #[arg(long, value_enum, default_value_t = CaseKind::Sensitive)]
+    case: CaseKind,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, clap::ValueEnum)]
+pub enum LengthKind {
+    Chars,
+    Bytes,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, clap::ValueEnum)]
+pub enum CaseKind {
+    Sensitive,
+    Lower,
+}
+
+impl From<EncodeArgs> for EncodeOptions {
+    fn from(args: EncodeArgs) -> Self {
+        Self {
+            min_group_size: args.min_group_size,
+            numerics: !args.no_numerics,
+            letters: !args.no_letters,
+            postamble_segments: args.postamble_segments,
+            optimise_for: match args.optimise_for {
+                LengthKind::Chars => Length::Chars,
+                LengthKind::Bytes => Length::Bytes,
+            },
+            case: match args.case {
+                CaseKind::Sensitive => Case::Sensitive,
+                CaseKind::Lower => Case::Lower,
+            },
+        }
+    }
+}
+
 fn output_or_stdout(path: Option<PathBuf>) -> Result<Box<dyn std::io::Write>, std::io::Error> {
     path.map(|path| {
         File::create(path)
@@ -69,27 +192,277 @@ fn output_or_stdout(path: Option<PathBuf>) -> Result<Box<dyn std::io::Write>, st
     .unwrap_or_else(|| Ok(Box::new(stdout())))
 }
 
+/// Print a tree of a brex's structure & compression stats
+fn explain_brex(output: &mut dyn Write, brex: &Brex) -> std::io::Result<()> {
+    let stats = brex.stats();
+    writeln!(output, "{brex}")?;
+    writeln!(
+        output,
+        "├─ chars: {} → {} ({:.1}%)",
+        stats.input.chars,
+        stats.output.chars,
+        stats.char_ratio() * 100.0
+    )?;
+    writeln!(
+        output,
+        "├─ bytes: {} → {} ({:.1}%)",
+        stats.input.bytes,
+        stats.output.bytes,
+        stats.byte_ratio() * 100.0
+    )?;
+    writeln!(
+        output,
+        "├─ preamble: {:?} ({} parts, {} parity)",
+        brex.preamble.unwrap_or_default(),
+        stats.preamble_parts,
+        stats.parity
+    )?;
+    writeln!(
+        output,
+        "├─ {} groups, {} suffixes, {} values collapsed into {} ranges",
+        stats.groups, stats.suffixes, stats.ranged_values, stats.ranges
+    )?;
+    for group in &brex.groups {
+        writeln!(output, "│  ├─ {:?}", group.prefix)?;
+        for (i, suffix) in group.suffixes.iter().enumerate() {
+            let branch = match i == group.suffixes.len() - 1 {
+                true => "└─",
+                false => "├─",
+            };
+            write!(output, "│  │  {branch} {:?}", suffix.suffix)?;
+            if suffix.has_block() {
+                let numerics = suffix.numerics.iter().flatten().map(|n| n.to_string());
+                let letters = suffix.letters.iter().flatten().map(|l| l.to_string());
+                let values = numerics.chain(letters).collect::<Vec<_>>();
+                write!(output, " {{{}}}", values.join(", "))?;
+            }
+            writeln!(output)?;
+        }
+    }
+    writeln!(
+        output,
+        "└─ postamble: {:?}",
+        brex.postamble.unwrap_or_default()
+    )
+}
+
+/// Print the size of a hashtable in each format
+fn report_sizes(fst: usize, packed: usize) {
+    println!(
+        "fst: {fst} bytes, brex-packed: {packed} bytes ({:.1}% of fst)",
+        packed as f64 / fst.max(1) as f64 * 100.0
+    );
+}
+
+/// Read a hashtable in `format`, writing it out as `hash path` lines sorted by path.
+/// The table is also converted to the other format, to report the size of both.
+fn decompress_table<H: Hash + Display>(
+    format: TableFormat,
+    bytes: Vec<u8>,
+    output: &Path,
+) -> miette::Result<()> {
+    let size = bytes.len();
+    let table = match format {
+        TableFormat::Fst => {
+            let set = fst::Set::new(bytes).into_diagnostic()?;
+            Hashtable::<H>::from_fst(set).into_diagnostic()?
+        }
+        TableFormat::Brex => {
+            let text = String::from_utf8(bytes).into_diagnostic()?;
+            Hashtable::<H>::from_brex_packed(&text).into_diagnostic()?
+        }
+    };
+
+    println!(
+        "table w/ {} entries decompressed from {size} bytes.",
+        table.hashes.len()
+    );
+    let packed = match format {
+        TableFormat::Fst => Some(table.to_brex_packed().into_diagnostic()?.len()),
+        TableFormat::Brex => None,
+    };
+
+    let mut entries = table.hashes.into_iter().collect::<Vec<_>>();
+    entries.sort_unstable_by(|a, b| a.1.cmp(&b.1));
+    match packed {
+        Some(packed) => report_sizes(size, packed),
+        None => {
+            let trie =
+                fst::Set::from_iter(entries.iter().map(|(_, value)| value)).into_diagnostic()?;
+            report_sizes(trie.as_fst().size(), size);
+        }
+    }
+
+    let mut output = BufWriter::new(File::create(output).into_diagnostic()?);
+    for (hash, value) in entries {
+        writeln!(output, "{hash} {value}").into_diagnostic()?;
+    }
+    Ok(())
+}
+
+/// Write the candidates of `template` whose hashes are in the `unknown` hashes file
+fn guess_hashes<H: Hash + Display + Send + Sync>(
+    template: &Brex,
+    unknown: &Path,
+    output: &mut dyn Write,
+) -> miette::Result<()> {
+    let mut hashes = HashSet::new();
+    for line in BufReader::new(File::open(unknown).into_diagnostic()?).lines() {
+        let line = line.into_diagnostic()?;
+        if let Some(hash) = line.split_whitespace().next() {
+            hashes.insert(H::from_str_radix(hash, 16).into_diagnostic()?);
+        }
+    }
+    let template = template.compile();
+    eprintln!(
+        "Trying {} candidates against {} unknown hashes...",
+        template.candidate_count(),
+        hashes.len()
+    );
+    let found = guess(&template, &hashes);
+    for (hash, path) in &found {
+        writeln!(output, "{hash} {path}").into_diagnostic()?;
+    }
+    eprintln!("Found {} hashes", found.len());
+    Ok(())
+}
+
 fn main() -> miette::Result<()> {
     let args = Cli::parse();
 
     match args.command {
         Commands::Brex(command) => match command {
-            BrexCommand::Encode { input, output } => {
+            BrexCommand::Encode {
+                input,
+                output,
+                unchecked,
+                explain,
+                pretty,
+                delta,
+                quiet,
+                chunk_size,
+                options,
+            } => {
                 let mut output = output_or_stdout(output).into_diagnostic()?;
-                let mut lines = BufReader::new(input.into_reader().into_diagnostic()?).lines();
-                while let Some(Ok(line)) = lines.next() {
-                    eprintln!("{line}");
-                    let encoded = brex::encode(line.trim()).into_diagnostic()?;
-                    writeln!(output, "{encoded}").into_diagnostic()?;
+                if pretty || delta {
+                    let mut text = String::new();
+                    input
+                        .into_reader()
+                        .into_diagnostic()?
+                        .read_to_string(&mut text)
+                        .into_diagnostic()?;
+                    let header = Header {
+                        dialect: if delta {
+                            Dialect::Delta
+                        } else {
+                            Dialect::Plain
+                        },
+                        delimiter: if pretty {
+                            Delimiter::Blank
+                        } else {
+                            Delimiter::Line
+                        },
+                        ..Default::default()
+                    };
+                    let (document, fallbacks) = BrexDocument::from_plaintext_checked(
+                        &text,
+                        header,
+                        &EncodeOptions::from(options),
+                    )
+                    .into_diagnostic()?;
+                    for (name, fallback) in fallbacks {
+                        if !quiet {
+                            eprintln!("{name}");
+                        }
+                        if let Some(fallback) = fallback {
+                            eprintln!("warning: fell back to {fallback} encoding");
+                        }
+                    }
+                    write!(output, "{document}").into_diagnostic()?;
+                    return Ok(());
+                }
+
+                let options = BatchOptions {
+                    encode: EncodeOptions::from(options),
+                    checked: !unchecked,
+                    chunk_size,
+                    comments: !explain,
+                };
+                if !explain {
+                    writeln!(output, "{}", Header::default()).into_diagnostic()?;
+                }
+                let input = BufReader::new(input.into_reader().into_diagnostic()?);
+                for encoded in brex::batch::encode_reader(input, &options) {
+                    let encoded = encoded.into_diagnostic()?;
+                    if encoded.comment {
+                        writeln!(output, "{}", encoded.input).into_diagnostic()?;
+                        continue;
+                    }
+                    if !quiet {
+                        eprintln!("{}", encoded.input);
+                    }
+                    if let Some(fallback) = encoded.fallback {
+                        eprintln!("warning: fell back to {fallback} encoding");
+                    }
+                    match explain {
+                        true => {
+                            let brex = Brex::parse(&encoded.encoded).into_diagnostic()?;
+                            explain_brex(&mut output, &brex).into_diagnostic()?
+                        }
+                        false => writeln!(output, "{}", document::escape(&encoded.encoded))
+                            .into_diagnostic()?,
+                    }
                 }
             }
-            BrexCommand::Decode { input, output } => {
+            BrexCommand::Diff { old, new } => {
+                let old = Brex::parse(&old).into_diagnostic()?;
+                let new = Brex::parse(&new).into_diagnostic()?;
+                print!("{}", old.diff(&new));
+            }
+            BrexCommand::Decode {
+                input,
+                output,
+                quiet,
+                delta,
+            } => {
                 let mut output = output_or_stdout(output).into_diagnostic()?;
-                let mut lines = BufReader::new(input.into_reader().into_diagnostic()?).lines();
-                while let Some(Ok(line)) = lines.next() {
-                    eprintln!("{line}");
-                    let encoded = brex::decode(line.trim()).into_diagnostic()?;
-                    writeln!(output, "{encoded}").into_diagnostic()?;
+                let input = BufReader::new(input.into_reader().into_diagnostic()?);
+                let mut reader = document::Reader::new(Header {
+                    dialect: if delta {
+                        Dialect::Delta
+                    } else {
+                        Dialect::Plain
+                    },
+                    ..Default::default()
+                });
+                let mut decoder = None;
+                let mut write_entry =
+                    |entry: document::Entry, dialect: Dialect| -> miette::Result<()> {
+                        if !quiet {
+                            eprintln!("{}", entry.brex);
+                        }
+                        let name = decoder
+                            .get_or_insert_with(|| document::Decoder::new(dialect))
+                            .decode(&entry)
+                            .into_diagnostic()?;
+                        for comment in &entry.comments {
+                            writeln!(output, "#{comment}").into_diagnostic()?;
+                        }
+                        writeln!(output, "{name}").into_diagnostic()
+                    };
+                for line in input.lines() {
+                    let line = line.into_diagnostic()?;
+                    if let Some(entry) = reader.read_line(&line).into_diagnostic()? {
+                        write_entry(entry, reader.header().dialect)?;
+                    }
+                }
+                let dialect = reader.header().dialect;
+                let (last, comments) = reader.finish().into_diagnostic()?;
+                if let Some(entry) = last {
+                    write_entry(entry, dialect)?;
+                }
+                for comment in comments {
+                    writeln!(output, "#{comment}").into_diagnostic()?;
                 }
             }
         },
@@ -98,11 +471,12 @@ fn main() -> miette::Result<()> {
                 kind,
                 input,
                 output,
+                format,
             } => {
                 let file = BufReader::new(File::open(&input).into_diagnostic()?);
 
                 println!("Reading {input:?}...");
-                let trie: fst::Set<Vec<u8>> = match kind {
+                let (trie, packed): (fst::Set<Vec<u8>>, String) = match kind {
                     HashKind::Bin => {
                         let mut entries = BTreeMap::new();
                         for line in file.lines() {
@@ -113,7 +487,8 @@ fn main() -> miette::Result<()> {
                         }
                         let table = Hashtable::from(entries);
                         println!("Compressing {} entries...", table.hashes.len());
-                        table.into()
+                        let packed = table.to_brex_packed().into_diagnostic()?;
+                        (table.into(), packed)
                     }
                     HashKind::Wad => {
                         let mut entries = BTreeMap::new();
@@ -125,7 +500,8 @@ fn main() -> miette::Result<()> {
                         }
                         let table = Hashtable::from(entries);
                         println!("Compressing {} entries...", table.hashes.len());
-                        table.into()
+                        let packed = table.to_brex_packed().into_diagnostic()?;
+                        (table.into(), packed)
                     }
                 };
 
@@ -135,43 +511,51 @@ fn main() -> miette::Result<()> {
                 let mut output = BufWriter::new(File::create(&output).into_diagnostic()?);
                 println!("Writing to {output:?}...");
 
-                output
-                    .write_all(trie.into_fst().as_bytes())
-                    .into_diagnostic()?;
+                let trie = trie.into_fst().into_inner();
+                report_sizes(trie.len(), packed.len());
+                let bytes = match format {
+                    TableFormat::Fst => trie,
+                    TableFormat::Brex => packed.into_bytes(),
+                };
+                output.write_all(&bytes).into_diagnostic()?;
             }
             HashCommand::Decompress {
                 kind,
                 input,
                 output,
+                format,
             } => {
                 println!("Decompressing {input:?}...");
-                let set = fst::Set::new(std::fs::read(&input).unwrap()).unwrap();
+                let bytes = std::fs::read(&input).into_diagnostic()?;
                 match kind {
-                    HashKind::Wad => {
-                        let table = Hashtable::<WadHash>::from_fst(set).into_diagnostic()?;
-
-                        println!("table w/ {} entries decompressed.", table.hashes.len());
-
-                        let mut output =
-                            BufWriter::new(std::fs::File::create(&output).into_diagnostic()?);
-                        let mut entries = table.hashes.into_iter().collect::<Vec<_>>();
-                        entries.sort_unstable_by(|a, b| a.1.cmp(&b.1));
-                        for (hash, value) in entries {
-                            writeln!(output, "{hash} {value}").into_diagnostic()?;
-                        }
+                    HashKind::Wad => decompress_table::<WadHash>(format, bytes, &output)?,
+                    HashKind::Bin => decompress_table::<BinHash>(format, bytes, &output)?,
+                }
+            }
+            HashCommand::Guess {
+                template,
+                unknown,
+                kind,
+                output,
+            } => {
+                let mut output = output_or_stdout(output).into_diagnostic()?;
+                let template = Brex::parse(&template).into_diagnostic()?;
+                match kind {
+                    HashKind::Wad => guess_hashes::<WadHash>(&template, &unknown, &mut output)?,
+                    HashKind::Bin => guess_hashes::<BinHash>(&template, &unknown, &mut output)?,
+                }
+            }
+            HashCommand::Linked { input, output } => {
+                let mut output = output_or_stdout(output).into_diagnostic()?;
+                let input = BufReader::new(input.into_reader().into_diagnostic()?);
+                for line in input.lines() {
+                    let line = line.into_diagnostic()?;
+                    if line.trim().is_empty() {
+                        continue;
                     }
-                    HashKind::Bin => {
-                        let table = Hashtable::<BinHash>::from_fst(set).into_diagnostic()?;
-
-                        println!("table w/ {} entries decompressed.", table.hashes.len());
-
-                        let mut output =
-                            BufWriter::new(std::fs::File::create(&output).into_diagnostic()?);
-                        let mut entries = table.hashes.into_iter().collect::<Vec<_>>();
-                        entries.sort_unstable_by(|a, b| a.1.cmp(&b.1));
-                        for (hash, value) in entries {
-                            writeln!(output, "{hash} {value}").into_diagnostic()?;
-                        }
+                    let linked = LinkedBins::parse(line.trim()).into_diagnostic()?;
+                    for (hash, path) in linked.wad_hashes() {
+                        writeln!(output, "{hash} {path}").into_diagnostic()?;
                     }
                 }
             }
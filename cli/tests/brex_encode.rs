@@ -0,0 +1,50 @@
+use std::{
+    io::Write as _,
+    process::{Command, Output, Stdio},
+};
+
+/// Run `poropack brex` with the given arguments & input
+fn brex(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_poropack"))
+        .arg("brex")
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn comments() {
+    let input = "# skins\naatrox_skins_skin0_skins_skin1.bin\n\n#\nahri.bin\n";
+    let output = brex(&["encode", "-q", "-"], input);
+    assert!(output.status.success());
+    let encoded = String::from_utf8(output.stdout).unwrap();
+    // blank lines & comments are passed through unencoded
+    assert_eq!(
+        encoded,
+        "\
+#!brex version=1 dialect=plain delimiter=line
+# skins
+aatrox❮_skins{_skin{0→1}}❯.bin
+
+#
+ahri.bin
+"
+    );
+
+    let output = brex(&["decode", "-q", "-"], &encoded);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "# skins\naatrox_skins_skin0_skins_skin1.bin\n#\nahri.bin\n"
+    );
+}
@@ -0,0 +1,47 @@
+use std::{
+    io::Write as _,
+    process::{Command, Output, Stdio},
+};
+
+use poro_hash::{Hash as _, WadHash};
+
+/// Run `poropack hash linked` on the given input
+fn hash_linked(input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_poropack"))
+        .args(["hash", "linked", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn paths() {
+    let output =
+        hash_linked("ahri❮_skins{_skin{0,1}}❯.bin\n\nkingporo_porofollower_skins_skin0_tx.bin\n");
+    assert!(output.status.success());
+
+    let expected = [
+        "data/characters/ahri/skins/skin0.bin",
+        "data/characters/ahri/skins/skin1.bin",
+        "data/characters/kingporo_porofollower/skins/skin0_tx.bin",
+    ]
+    .map(|path| format!("{} {path}\n", WadHash::hash_str(path)))
+    .concat();
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), expected);
+}
+
+#[test]
+fn unpaired_folder() {
+    let output = hash_linked("aatrox_skins.bin\n");
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+}
@@ -0,0 +1,50 @@
+//! Discovering unknown hashes from brex templates
+//!
+//! A brex template, used as a [`Pattern`], describes the candidate paths to try,
+//! e.g `data/characters/ahri/❮skins/{root,skin{0→120}}❯.bin`. Each candidate is expanded lazily & hashed,
+//! and kept if its hash is one of the unknown hashes.
+//!
+//! ```
+//! use std::collections::HashSet;
+//!
+//! use brex::Brex;
+//! use poro_hash::{Hash as _, WadHash, guess::guess};
+//!
+//! let path = "data/characters/ahri/skins/skin42.bin";
+//! let unknown = HashSet::from([WadHash::hash_str(path)]);
+//! let template = Brex::parse("data/characters/ahri/❮skins/{root,skin{0→120}}❯.bin").unwrap();
+//! let found = guess(&template.compile(), &unknown);
+//! assert_eq!(found, [(WadHash::hash_str(path), path.to_string())]);
+//! ```
+
+use std::collections::HashSet;
+
+use brex::pattern::Pattern;
+use rayon::iter::{ParallelBridge as _, ParallelIterator as _};
+
+use crate::Hash;
+
+/// Lazily hash each candidate of `template`, yielding those whose hash is in `unknown`, in the order the template lists them
+pub fn guesses<'a, H: Hash>(
+    template: &'a Pattern,
+    unknown: &'a HashSet<H>,
+) -> impl Iterator<Item = (H, String)> + 'a {
+    template
+        .candidates()
+        .map(|candidate| (H::hash_str(&candidate), candidate))
+        .filter(|(hash, _)| unknown.contains(hash))
+}
+
+/// Hash each candidate of `template` in parallel, returning those whose hash is in `unknown`, sorted by path
+pub fn guess<H: Hash + Send + Sync>(template: &Pattern, unknown: &HashSet<H>) -> Vec<(H, String)> {
+    let mut found = template
+        .candidates()
+        .par_bridge()
+        .filter_map(|candidate| {
+            let hash = H::hash_str(&candidate);
+            unknown.contains(&hash).then_some((hash, candidate))
+        })
+        .collect::<Vec<_>>();
+    found.sort_unstable_by(|a, b| a.1.cmp(&b.1));
+    found
+}
@@ -7,6 +7,13 @@ use std::{
 
 use derive_more as dm;
 
+#[cfg(feature = "brex")]
+pub mod guess;
+#[cfg(feature = "brex")]
+pub mod linked;
+#[cfg(feature = "brex")]
+pub mod packed;
+
 pub trait Hash: std::hash::Hash + Ord + Eq + FromStrRadix + Default {
     fn hash_str(str: impl AsRef<str>) -> Self;
 }
@@ -114,7 +121,6 @@ mod trie_impl {
     }
 }
 
-use fnv_rs::FnvHasher;
 #[cfg(feature = "fst")]
 pub use fst;
 use ltk_hash::fnv1a;
@@ -0,0 +1,189 @@
+//! League's linked bin names
+//!
+//! A champion's bins are linked together under a single concatenated name, made of the champion followed by
+//! `_folder_file` pairs, such as `aatrox_skins_root_skins_skin0_skins_skin1.bin`.
+//! Each pair is the bin at `data/characters/{champion}/{folder}/{file}.bin`.
+//!
+//! Champion & file names may themselves contain `_`, so pairs are split on the known [`FOLDERS`]
+//! (see [`LinkedBins::from_brex_with()`] for others): a name is the champion up to the first folder,
+//! then each folder followed by its file, up to the next folder.
+//!
+//! These names are usually stored brex encoded (`aatrox❮_skins{_root,_skin{0→1}}❯.bin`), so they are read via the brex IR.
+//!
+//! ```
+//! use poro_hash::linked::LinkedBins;
+//!
+//! let linked = LinkedBins::parse("aatrox❮_skins{_root,_skin{0→1}}❯.bin").unwrap();
+//! assert_eq!(linked.champion, "aatrox");
+//! assert_eq!(
+//!     linked.paths().collect::<Vec<_>>(),
+//!     [
+//!         "data/characters/aatrox/skins/root.bin",
+//!         "data/characters/aatrox/skins/skin0.bin",
+//!         "data/characters/aatrox/skins/skin1.bin",
+//!     ]
+//! );
+//! ```
+
+use brex::Brex;
+
+use crate::{Hash, WadHash};
+
+/// The folders of a champion's bins
+pub const FOLDERS: &[&str] = &["animations", "skins"];
+
+#[derive(thiserror::Error, Debug)]
+pub enum LinkedBinsError {
+    #[error(transparent)]
+    Parse(#[from] brex::parse::Error),
+    #[error("Linked bin name {0:?} has no champion")]
+    MissingChampion(String),
+    #[error("Linked bin name {0:?} has a folder without a file")]
+    UnpairedFolder(String),
+}
+
+/// A bin linked into a concatenated name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkedBin {
+    pub folder: String,
+    pub file: String,
+}
+
+/// The champion & bins of a linked bin name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkedBins {
+    pub champion: String,
+    pub bins: Vec<LinkedBin>,
+    /// The extension of every bin, without its `.`
+    pub extension: String,
+}
+
+impl LinkedBins {
+    /// Parse a linked bin name, either as plaintext or brex encoded.
+    /// Any directory before the name (such as `data/`) is ignored.
+    pub fn parse(name: &str) -> Result<Self, LinkedBinsError> {
+        Self::from_brex(&Brex::parse(name)?)
+    }
+
+    /// Read the linked bins a brex expands to, splitting pairs on the known [`FOLDERS`]
+    pub fn from_brex(brex: &Brex) -> Result<Self, LinkedBinsError> {
+        Self::from_brex_with(brex, FOLDERS)
+    }
+
+    /// Read the linked bins a brex expands to, splitting pairs on the given folders
+    pub fn from_brex_with(brex: &Brex, folders: &[&str]) -> Result<Self, LinkedBinsError> {
+        let name = brex.expand();
+        let (stem, extension) = name.rsplit_once('.').unwrap_or((&name, "bin"));
+        let stem = stem.rsplit_once('/').map_or(stem, |(_, stem)| stem);
+
+        // each run of parts up to the next folder
+        let mut runs = vec![Vec::new()];
+        for part in stem.split('_') {
+            if folders.contains(&part) {
+                runs.push(Vec::new());
+            }
+            runs.last_mut().unwrap().push(part);
+        }
+
+        let champion = runs.remove(0).join("_");
+        if champion.is_empty() {
+            return Err(LinkedBinsError::MissingChampion(name.clone()));
+        }
+        let bins = runs
+            .into_iter()
+            .map(|run| match run[1..].join("_") {
+                file if file.is_empty() => Err(LinkedBinsError::UnpairedFolder(name.clone())),
+                file => Ok(LinkedBin {
+                    folder: run[0].to_string(),
+                    file,
+                }),
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            champion,
+            bins,
+            extension: extension.to_string(),
+        })
+    }
+
+    /// The full path of each bin, in the order they are linked
+    pub fn paths(&self) -> impl Iterator<Item = String> + '_ {
+        self.bins.iter().map(|bin| {
+            format!(
+                "data/characters/{}/{}/{}.{}",
+                self.champion, bin.folder, bin.file, self.extension
+            )
+        })
+    }
+
+    /// Each bin's path alongside its hash
+    pub fn hashes<H: Hash>(&self) -> impl Iterator<Item = (H, String)> + '_ {
+        self.paths().map(|path| (H::hash_str(&path), path))
+    }
+
+    /// Each bin's path alongside its [`WadHash`]
+    pub fn wad_hashes(&self) -> impl Iterator<Item = (WadHash, String)> + '_ {
+        self.hashes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LinkedBin, LinkedBins, LinkedBinsError};
+
+    fn bin(folder: &str, file: &str) -> LinkedBin {
+        LinkedBin {
+            folder: folder.to_string(),
+            file: file.to_string(),
+        }
+    }
+
+    #[test]
+    fn underscores() {
+        let linked =
+            LinkedBins::parse("kingporo_porofollower_skins_skin0_tx_animations_skin0.bin").unwrap();
+        assert_eq!(linked.champion, "kingporo_porofollower");
+        assert_eq!(
+            linked.bins,
+            [bin("skins", "skin0_tx"), bin("animations", "skin0")]
+        );
+    }
+
+    #[test]
+    fn directory() {
+        let linked = LinkedBins::parse("data/ahri❮_skins{_skin{0→1}}❯.bin").unwrap();
+        assert_eq!(linked.champion, "ahri");
+        assert_eq!(linked.extension, "bin");
+        assert_eq!(linked.bins, [bin("skins", "skin0"), bin("skins", "skin1")]);
+    }
+
+    #[test]
+    fn missing_champion() {
+        assert!(matches!(
+            LinkedBins::parse("_skins_skin0.bin"),
+            Err(LinkedBinsError::MissingChampion(_))
+        ));
+        assert!(matches!(
+            LinkedBins::parse("skins_skin0.bin"),
+            Err(LinkedBinsError::MissingChampion(_))
+        ));
+    }
+
+    #[test]
+    fn unpaired_folder() {
+        for name in [
+            "aatrox_skins.bin",
+            "aatrox_skins_skins_skin0.bin",
+            "aatrox_skins__skins_skin0.bin",
+        ] {
+            assert!(
+                matches!(
+                    LinkedBins::parse(name),
+                    Err(LinkedBinsError::UnpairedFolder(_))
+                ),
+                "{name}"
+            );
+        }
+    }
+}
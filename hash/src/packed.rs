@@ -0,0 +1,199 @@
+//! Brex-packed hashtables
+//!
+//! Paths are grouped by directory & extension, and each group's file names are brex encoded together,
+//! so runs like `skin0.bin` … `skin84.bin` collapse into a single `{0→84}`.
+//!
+//! ```text
+//! #!brex-hashtable version=1
+//! data/characters/ahri/skins/⇥.bin⇥❮_/{_root,_skin{0→84}}❯
+//! ```
+//! After the [`HEADER`], each line is a tab (shown as `⇥`) separated directory (with its trailing `/`), extension (with its leading `.`)
+//! & brex. The brex expands to the group's file names, each preceded by [`SEPARATOR`].
+//! `/` never appears within a file name, so the separator is unambiguous.
+//! Paths containing a tab or line break can't be packed.
+
+use std::collections::BTreeMap;
+
+use rayon::iter::{IntoParallelIterator as _, ParallelIterator as _};
+
+use crate::{Hash, Hashtable};
+
+/// The first line of a brex-packed hashtable
+pub const HEADER: &str = "#!brex-hashtable version=1";
+/// Precedes each file name in a group's expanded brex
+pub const SEPARATOR: &str = "_/_";
+
+#[derive(thiserror::Error, Debug)]
+pub enum PackedError {
+    #[error("Missing or unsupported header, expected {HEADER:?}")]
+    InvalidHeader,
+    #[error("line {0}: expected `directory<TAB>extension<TAB>brex`")]
+    InvalidLine(usize),
+    #[error("Path {0:?} contains a tab or line break")]
+    InvalidPath(String),
+    #[error("line {line}: {source}")]
+    Parse {
+        line: usize,
+        source: brex::parse::Error,
+    },
+    #[error(transparent)]
+    Encode(#[from] brex::encode::Error),
+}
+
+/// Split a path into its directory (with its trailing `/`), file name & extension (with its leading `.`)
+fn split_path(path: &str) -> (&str, &str, &str) {
+    let (dir, file) = path.split_at(path.rfind('/').map_or(0, |slash| slash + 1));
+    let (name, ext) = file.split_at(file.rfind('.').unwrap_or(file.len()));
+    (dir, name, ext)
+}
+
+impl<H: Hash> Hashtable<H> {
+    /// Write the table's paths as a brex-packed hashtable. See the [module docs](crate::packed).
+    ///
+    /// ```
+    /// use poro_hash::{Hash as _, Hashtable, WadHash};
+    ///
+    /// let paths = ["root", "skin0", "skin1", "skin2"].map(|name| format!("data/characters/ahri/skins/{name}.bin"));
+    /// let table = Hashtable::from(paths.map(|path| (WadHash::hash_str(&path), path)));
+    /// let packed = table.to_brex_packed().unwrap();
+    /// assert_eq!(packed.lines().nth(1), Some("data/characters/ahri/skins/\t.bin\t❮_/{_root,_skin{0→2}}❯"));
+    /// assert_eq!(Hashtable::<WadHash>::from_brex_packed(&packed).unwrap().hashes, table.hashes);
+    /// ```
+    pub fn to_brex_packed(&self) -> Result<String, PackedError> {
+        let mut groups = BTreeMap::<(&str, &str), Vec<&str>>::new();
+        for path in self.hashes.values() {
+            if path.contains(['\t', '\n', '\r']) {
+                return Err(PackedError::InvalidPath(path.clone()));
+            }
+            let (dir, name, ext) = split_path(path);
+            groups.entry((dir, ext)).or_default().push(name);
+        }
+
+        let lines = groups
+            .into_iter()
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .map(|((dir, ext), mut names)| {
+                names.sort_unstable();
+                let joined = names
+                    .iter()
+                    .flat_map(|name| [SEPARATOR, name])
+                    .collect::<String>();
+                let (encoded, _) = brex::encode_checked(&joined)?;
+                Ok(format!("{dir}\t{ext}\t{encoded}\n"))
+            })
+            .collect::<Result<Vec<_>, PackedError>>()?;
+
+        let mut out = format!("{HEADER}\n");
+        out.extend(lines);
+        Ok(out)
+    }
+
+    /// Read a brex-packed hashtable, hashing each path. See the [module docs](crate::packed).
+    pub fn from_brex_packed(input: &str) -> Result<Self, PackedError> {
+        let mut lines = input.lines().enumerate();
+        if lines.next().map(|(_, header)| header) != Some(HEADER) {
+            return Err(PackedError::InvalidHeader);
+        }
+
+        let mut parser = brex::parse::Parser::new();
+        let mut hashes = Vec::new();
+        for (i, line) in lines {
+            if line.is_empty() {
+                continue;
+            }
+            let mut fields = line.splitn(3, '\t');
+            let (Some(dir), Some(ext), Some(encoded)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                return Err(PackedError::InvalidLine(i + 1));
+            };
+            let names = parser
+                .decode(encoded)
+                .map_err(|source| PackedError::Parse {
+                    line: i + 1,
+                    source,
+                })?;
+            let Some(names) = names.strip_prefix(SEPARATOR) else {
+                return Err(PackedError::InvalidLine(i + 1));
+            };
+            for name in names.split(SEPARATOR) {
+                let path = format!("{dir}{name}{ext}");
+                hashes.push((H::hash_str(&path), path));
+            }
+        }
+        Ok(hashes.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PackedError, HEADER};
+    use crate::{Hash as _, Hashtable, WadHash};
+
+    fn table(paths: &[&str]) -> Hashtable<WadHash> {
+        Hashtable::from(
+            paths
+                .iter()
+                .map(|path| (WadHash::hash_str(path), path.to_string())),
+        )
+    }
+
+    #[test]
+    fn roundtrips() {
+        let table = table(&[
+            "data/characters/ahri/skins/skin0.bin",
+            "data/characters/ahri/skins/skin1.bin",
+            "data/characters/ahri/ahri",
+            "data/characters/ahri/ahri.bin",
+            "readme.txt",
+            "license",
+            ".gitignore",
+        ]);
+        let packed = table.to_brex_packed().unwrap();
+        let lines = packed.lines().collect::<Vec<_>>();
+        // no extension
+        assert!(lines.contains(&"data/characters/ahri/\t\t_/_ahri"));
+        assert!(lines.contains(&"\t\t_/_license"));
+        // no directory
+        assert!(lines.contains(&"\t.txt\t_/_readme"));
+        assert!(lines.contains(&"\t.gitignore\t_/_"));
+        let unpacked = Hashtable::<WadHash>::from_brex_packed(&packed).unwrap();
+        assert_eq!(unpacked.hashes, table.hashes);
+    }
+
+    #[test]
+    fn invalid_path() {
+        for path in ["data/a\tb.bin", "data/a\nb.bin", "data/a.bin\r"] {
+            assert!(matches!(
+                table(&[path]).to_brex_packed(),
+                Err(PackedError::InvalidPath(invalid)) if invalid == path
+            ));
+        }
+    }
+
+    #[test]
+    fn invalid_header() {
+        for input in ["", "data/\t.bin\t❮_/a❯\n", "#!brex-hashtable version=2\n"] {
+            assert!(matches!(
+                Hashtable::<WadHash>::from_brex_packed(input),
+                Err(PackedError::InvalidHeader)
+            ));
+        }
+    }
+
+    #[test]
+    fn invalid_line() {
+        for (line, number) in [
+            ("data/\t.bin", 2),
+            ("data/", 2),
+            ("data/\t.bin\ta", 2),
+            ("\ndata/\t.bin", 3),
+        ] {
+            assert!(matches!(
+                Hashtable::<WadHash>::from_brex_packed(&format!("{HEADER}\n{line}\n")),
+                Err(PackedError::InvalidLine(invalid)) if invalid == number
+            ));
+        }
+    }
+}
@@ -0,0 +1,266 @@
+//! Python bindings for brex & poro_hash.
+//!
+//! Built with [maturin](https://www.maturin.rs), see `pyproject.toml`.
+#![deny(warnings)]
+
+use std::{fs::File, io::BufReader, path::PathBuf};
+
+use poro_hash::{BinHash, Hash as _, Hashtable, WadHash, fst};
+use pyo3::{
+    exceptions::{PyKeyError, PyValueError},
+    prelude::*,
+    types::{PyDict, PyList, PyRange},
+};
+
+#[pymodule]
+fn poropack(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let brex = PyModule::new(m.py(), "brex")?;
+    brex.add_function(wrap_pyfunction!(encode, &brex)?)?;
+    brex.add_function(wrap_pyfunction!(decode, &brex)?)?;
+    brex.add_function(wrap_pyfunction!(parse, &brex)?)?;
+    m.add_submodule(&brex)?;
+
+    m.add_class::<PyWadHash>()?;
+    m.add_class::<PyBinHash>()?;
+    m.add_class::<PyHashtable>()?;
+    Ok(())
+}
+
+/// Encode text to a brex string.
+#[pyfunction]
+fn encode(input: &str) -> PyResult<String> {
+    brex::encode(input).map_err(|err| PyValueError::new_err(err.to_string()))
+}
+
+/// Parse and expand a brex string.
+#[pyfunction]
+fn decode(encoded: &str) -> PyResult<String> {
+    brex::decode(encoded).map_err(|err| PyValueError::new_err(err.to_string()))
+}
+
+/// Parse a brex string into its structure.
+///
+/// Returns a dict of `preamble`, `groups` & `postamble`. Each group is a dict of `prefix` & `suffixes`,
+/// and each suffix a dict of `suffix`, `numerics` - a list of `range`s, or `None`,
+/// & `letters` - a list of inclusive `(start, end)` letter sequence pairs, or `None`.
+#[pyfunction]
+fn parse<'py>(py: Python<'py>, encoded: &str) -> PyResult<Bound<'py, PyDict>> {
+    let parsed =
+        brex::Brex::parse(encoded).map_err(|err| PyValueError::new_err(err.to_string()))?;
+
+    let groups = PyList::empty(py);
+    for group in &parsed.groups {
+        let suffixes = PyList::empty(py);
+        for suffix in &group.suffixes {
+            let numerics = match &suffix.numerics {
+                Some(numerics) => {
+                    let ranges = PyList::empty(py);
+                    for numeric in numerics {
+                        let range =
+                            PyRange::new(py, numeric.start() as isize, numeric.end() as isize + 1)?;
+                        ranges.append(range)?;
+                    }
+                    ranges.into_any()
+                }
+                None => py.None().into_bound(py),
+            };
+            let letters = suffix.letters.as_ref().map(|letters| {
+                letters
+                    .iter()
+                    .map(|alphabetic| {
+                        let (start, end) = (
+                            brex::Alphabetic::Single(alphabetic.start()),
+                            brex::Alphabetic::Single(alphabetic.end()),
+                        );
+                        (start.to_string(), end.to_string())
+                    })
+                    .collect::<Vec<_>>()
+            });
+            let dict = PyDict::new(py);
+            dict.set_item("suffix", suffix.suffix)?;
+            dict.set_item("numerics", numerics)?;
+            dict.set_item("letters", letters)?;
+            suffixes.append(dict)?;
+        }
+        let dict = PyDict::new(py);
+        dict.set_item("prefix", group.prefix)?;
+        dict.set_item("suffixes", suffixes)?;
+        groups.append(dict)?;
+    }
+
+    let dict = PyDict::new(py);
+    dict.set_item("preamble", parsed.preamble)?;
+    dict.set_item("groups", groups)?;
+    dict.set_item("postamble", parsed.postamble)?;
+    Ok(dict)
+}
+
+/// A 64-bit hash of a WAD file path.
+#[pyclass(name = "WadHash", frozen, eq, hash, ord, skip_from_py_object)]
+#[derive(PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy)]
+struct PyWadHash(u64);
+
+#[pymethods]
+impl PyWadHash {
+    #[new]
+    fn new(value: u64) -> Self {
+        Self(value)
+    }
+
+    /// Hash a path (case-insensitively)
+    #[staticmethod]
+    fn hash_str(path: &str) -> Self {
+        Self(WadHash::hash_str(path).0)
+    }
+
+    #[getter]
+    fn value(&self) -> u64 {
+        self.0
+    }
+
+    fn __index__(&self) -> u64 {
+        self.0
+    }
+
+    fn __str__(&self) -> String {
+        WadHash(self.0).to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("WadHash(0x{})", self.__str__())
+    }
+}
+
+/// A 32-bit hash of a bin entry name.
+#[pyclass(name = "BinHash", frozen, eq, hash, ord, skip_from_py_object)]
+#[derive(PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy)]
+struct PyBinHash(u32);
+
+#[pymethods]
+impl PyBinHash {
+    #[new]
+    fn new(value: u32) -> Self {
+        Self(value)
+    }
+
+    /// Hash a name (case-insensitively)
+    #[staticmethod]
+    fn hash_str(name: &str) -> Self {
+        Self(BinHash::hash_str(name).0)
+    }
+
+    #[getter]
+    fn value(&self) -> u32 {
+        self.0
+    }
+
+    fn __index__(&self) -> u32 {
+        self.0
+    }
+
+    fn __str__(&self) -> String {
+        BinHash(self.0).to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("BinHash(0x{})", self.__str__())
+    }
+}
+
+enum Table {
+    Wad(Hashtable<WadHash>),
+    Bin(Hashtable<BinHash>),
+}
+
+/// A table of known hashes & the strings they were hashed from.
+///
+/// `kind` is either `"wad"` or `"bin"`.
+#[pyclass(name = "Hashtable", frozen)]
+struct PyHashtable(Table);
+
+fn read_error(err: impl ToString) -> PyErr {
+    PyValueError::new_err(err.to_string())
+}
+
+#[pymethods]
+impl PyHashtable {
+    /// Load a CommunityDragon/Data style text hashtable (`<hex hash> <path>` per line)
+    #[staticmethod]
+    fn from_text(path: PathBuf, kind: &str) -> PyResult<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+        Ok(Self(match kind {
+            "wad" => Table::Wad(Hashtable::read_hashtable_file(&mut reader).map_err(read_error)?),
+            "bin" => Table::Bin(Hashtable::read_hashtable_file(&mut reader).map_err(read_error)?),
+            kind => return Err(unknown_kind(kind)),
+        }))
+    }
+
+    /// Load a hashtable compressed by `poropack hash compress`
+    #[staticmethod]
+    fn from_fst(path: PathBuf, kind: &str) -> PyResult<Self> {
+        let set = fst::Set::new(std::fs::read(path)?).map_err(read_error)?;
+        Ok(Self(match kind {
+            "wad" => Table::Wad(Hashtable::from_fst(set).map_err(read_error)?),
+            "bin" => Table::Bin(Hashtable::from_fst(set).map_err(read_error)?),
+            kind => return Err(unknown_kind(kind)),
+        }))
+    }
+
+    /// Write this table's strings as an fst set, loadable with [`Self::from_fst`]
+    fn write_fst(&self, path: PathBuf) -> PyResult<()> {
+        let set: fst::Set<Vec<u8>> = match &self.0 {
+            Table::Wad(table) => table.clone().into(),
+            Table::Bin(table) => table.clone().into(),
+        };
+        std::fs::write(path, set.as_fst().as_bytes())?;
+        Ok(())
+    }
+
+    /// Either `"wad"` or `"bin"`
+    #[getter]
+    fn kind(&self) -> &'static str {
+        match self.0 {
+            Table::Wad(_) => "wad",
+            Table::Bin(_) => "bin",
+        }
+    }
+
+    /// Look up the string for a hash, returning `default` if it is unknown
+    #[pyo3(signature = (hash, default=None))]
+    fn get(&self, hash: u64, default: Option<String>) -> Option<String> {
+        self.lookup(hash).map(str::to_string).or(default)
+    }
+
+    fn __getitem__(&self, hash: u64) -> PyResult<String> {
+        self.lookup(hash)
+            .map(str::to_string)
+            .ok_or_else(|| PyKeyError::new_err(hash))
+    }
+
+    fn __contains__(&self, hash: u64) -> bool {
+        self.lookup(hash).is_some()
+    }
+
+    fn __len__(&self) -> usize {
+        match &self.0 {
+            Table::Wad(table) => table.hashes.len(),
+            Table::Bin(table) => table.hashes.len(),
+        }
+    }
+}
+
+impl PyHashtable {
+    fn lookup(&self, hash: u64) -> Option<&str> {
+        match &self.0 {
+            Table::Wad(table) => table.hashes.get(&WadHash(hash)),
+            Table::Bin(table) => table.hashes.get(&BinHash(u32::try_from(hash).ok()?)),
+        }
+        .map(String::as_str)
+    }
+}
+
+fn unknown_kind(kind: &str) -> PyErr {
+    PyValueError::new_err(format!(
+        "unknown hashtable kind {kind:?}, expected \"wad\" or \"bin\""
+    ))
+}
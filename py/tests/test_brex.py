@@ -0,0 +1,70 @@
+from pathlib import Path
+
+import pytest
+
+from poropack import brex
+
+CASES = Path(__file__).parents[2] / "brex" / "cases"
+
+
+@pytest.mark.parametrize("case", sorted(CASES.glob("*.in")), ids=lambda p: p.stem)
+def test_roundtrip(case):
+    for line in case.read_text().splitlines():
+        if line.startswith("#"):
+            continue
+        assert brex.decode(brex.encode(line)) == line
+
+
+def test_encode():
+    assert (
+        brex.encode("aatrox_skins_root_skins_skin0_skins_skin1_skins_skin2.bin")
+        == "aatrox❮_skins{_root,_skin{0→2}}❯.bin"
+    )
+
+
+def test_parse():
+    parsed = brex.parse("aatrox❮_skins{_root,_skin{0→2,5}}❯.bin")
+    assert parsed == {
+        "preamble": "aatrox",
+        "groups": [
+            {
+                "prefix": "_skins",
+                "suffixes": [
+                    {"suffix": "_root", "numerics": None, "letters": None},
+                    {
+                        "suffix": "_skin",
+                        "numerics": [range(0, 3), range(5, 6)],
+                        "letters": None,
+                    },
+                ],
+            }
+        ],
+        "postamble": ".bin",
+    }
+
+
+def test_parse_letters():
+    parsed = brex.parse("ahri❮_base{_variant{a→c,f}}❯.bin")
+    assert parsed["groups"] == [
+        {
+            "prefix": "_base",
+            "suffixes": [
+                {
+                    "suffix": "_variant",
+                    "numerics": None,
+                    "letters": [("a", "c"), ("f", "f")],
+                },
+            ],
+        }
+    ]
+    assert brex.decode("ahri❮_base{_variant{a→c,f}}❯.bin") == (
+        "ahri_base_varianta_base_variantb_base_variantc_base_variantf.bin"
+    )
+
+
+def test_parse_plaintext():
+    assert brex.parse("plain.bin") == {
+        "preamble": "plain.bin",
+        "groups": [],
+        "postamble": None,
+    }
@@ -0,0 +1,45 @@
+from poropack import BinHash, Hashtable, WadHash
+
+PATHS = [
+    "data/characters/aatrox/skins/root.bin",
+    "data/characters/aatrox/skins/skin0.bin",
+]
+
+
+def test_wad_hash():
+    # xxh64 of the empty string
+    assert WadHash.hash_str("").value == 0xEF46DB3751D8E999
+    assert WadHash.hash_str("DATA/Characters") == WadHash.hash_str("data/characters")
+    assert str(WadHash(0xAB)) == "00000000000000ab"
+
+
+def test_bin_hash():
+    # fnv1a offset basis
+    assert BinHash.hash_str("").value == 0x811C9DC5
+    assert BinHash.hash_str("Skin0") == BinHash.hash_str("skin0")
+    assert str(BinHash(0xAB)) == "000000ab"
+
+
+def test_hashtable_from_text(tmp_path):
+    table_file = tmp_path / "hashes.game.txt"
+    table_file.write_text("".join(f"{WadHash.hash_str(p)} {p}\n" for p in PATHS))
+
+    table = Hashtable.from_text(table_file, "wad")
+    assert table.kind == "wad"
+    assert len(table) == len(PATHS)
+    for path in PATHS:
+        hash = WadHash.hash_str(path)
+        assert hash in table
+        assert table[hash] == path
+    assert table.get(0) is None
+
+
+def test_hashtable_fst(tmp_path):
+    table_file = tmp_path / "hashes.binentries.txt"
+    table_file.write_text("".join(f"{BinHash.hash_str(p)} {p}\n" for p in PATHS))
+    fst_file = tmp_path / "hashes.binentries.fst"
+
+    Hashtable.from_text(table_file, "bin").write_fst(fst_file)
+    table = Hashtable.from_fst(fst_file, "bin")
+    assert table.kind == "bin"
+    assert sorted(table[BinHash.hash_str(p)] for p in PATHS) == sorted(PATHS)